@@ -1,7 +1,8 @@
 
 fn main() -> Result<(), Box<dyn std::error::Error>>{
+    // the server side is only needed for the mock server
     tonic_build::configure()
-        .build_server(false)
+        .build_server(std::env::var_os("CARGO_FEATURE_MOCK_SERVER").is_some())
         .compile(
             &["proto/frontend_proto/frontend.proto",
                 "proto/frontend_proto/frontend_messages.proto"
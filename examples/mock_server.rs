@@ -0,0 +1,23 @@
+// serves a small demo inventory on the default address so the GUI or CLI can be tried without the real server:
+// cargo run --example mock_server --features mock-server
+use std::io;
+use std::net::SocketAddr;
+use cs4471_sims_cli_client::mock::MockServer;
+
+fn main() -> io::Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let server = MockServer::start_on(SocketAddr::from(([127, 0, 0, 1], 50051))).await?;
+        server.add_user("demo", "demo");
+        server.add_shelf("A1", 4);
+        server.add_shelf("B2", 2);
+        server.add_item("A1", "Paper towels", 20, 499);
+        server.add_item("A1", "Dish soap", 3, 249);
+        server.add_item("B2", "Batteries", 48, 1299);
+
+        println!("Serving on {}, log in as demo/demo. Press Enter to stop.", server.address());
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        Ok(())
+    })
+}
@@ -0,0 +1,133 @@
+## Login
+app-title = SIMS Inventory Management System
+choose-profile = Choose a profile
+server = Server
+username = Username
+password = Password
+remember-me = Remember me
+remember-me-unavailable = Set SIMS_PASSPHRASE to be able to stay logged in
+login = Login
+logging-in = Logging In...
+error-not-connected = Could not connect to server
+error-session-expired = Your saved login has expired, please log in again
+error-server = The server rejected the request: { $reason }
+
+## Tabs and navigation
+tab-shelves = Shelves
+tab-items = Items
+tab-dashboard = Dashboard
+tab-move-hint = Click the tab whose place it should take, Esc cancels
+back = Back
+search-items = Search items (Ctrl+F)
+
+## Shelves
+my-shelves = My Shelves
+column-name = Name
+column-slots = # of Slots
+column-occupancy = Estimated occupancy
+column-percent-full = % Full (est.)
+column-actions = Actions
+slot-count = Slots: { $count }
+slots-estimated = Slot figures are estimates: the server doesn't report slots, so each item is counted as filling its own slot of { $capacity } units
+open = Open
+
+## Items
+all-items = All Items
+column-item = Item
+column-id = ID
+column-description = Description
+column-shelf = Shelf
+column-price = Price
+column-stock = Stock
+viewing-shelf = Viewing { $shelf }
+invalid-shelf-tab = Invalid shelf tab
+
+## Dashboard
+total-skus = Total SKUs
+total-units = Total Units
+inventory-value = Inventory Value
+shelf-utilisation = Estimated Shelf Utilisation
+stock-distribution = Stock Distribution
+
+## Suggestions
+suggestions = Suggestions
+no-suggestions = Nothing to suggest, stock levels look good
+dismiss = Dismiss
+suggest-restock = Restock item { $item } on shelf { $shelf } to { $target }
+suggest-consolidate = Consolidate slots { $slots } on shelf { $shelf }, estimated to be half empty
+
+## Editing
+nothing-to-edit = Nothing to edit
+editing-item = Editing item with id { $item } in shelf with id { $shelf }
+editing-slot = Editing slot with id { $slot } in shelf with id { $shelf }
+editing-shelf = Editing shelf with id { $shelf }
+new-shelf = New Shelf
+field-shelf = Shelf
+field-name = Name
+field-slots = Slots
+placeholder-shelf-name = Shelf name
+create = Create
+error-min-slots = Your shelf must have at least 1 slot
+error-shelf-empty = A shelf id is required
+error-slots-natural = Slots must be a natural number
+
+## Terminal interface
+tui-keys = ←/→ tabs  ↑/↓ select  Enter open  n new shelf  r refresh  w close tab  q quit
+tui-form-keys = Tab next field  Enter save  Esc cancel
+tui-saved = Saved
+tui-refreshed = Refreshed
+
+## Command palette
+command-palette = Command Palette
+palette-placeholder = Type a command, shelf or item
+command-new = New shelf
+command-refresh = Refresh
+command-go-shelves = Go to Shelves
+command-go-items = Go to Items
+command-go-dashboard = Go to Dashboard
+command-suggestions = Show suggestions
+command-settings = Settings
+palette-shelf = Shelf { $shelf }
+palette-item = Item { $item } { $description } on { $shelf }
+
+## Settings
+settings = Settings
+setting-theme = Theme
+setting-language = Language
+theme-light = Light
+theme-dark = Dark
+theme-system = Follow system
+locale-system = System default
+palettes-path = Custom palettes are read from { $path }
+palettes-unavailable = Custom palettes are unavailable without a home directory
+setting-scale = Interface scale
+scale-hint = Ctrl+= and Ctrl+- also change the scale, Ctrl+0 resets it
+
+## Account
+account = Account
+signed-in-as = Signed in as { $username } on { $server }
+confirm-password = Confirm password
+error-password-mismatch = The new passwords do not match
+switch-profile = Switch profile
+logout = Log out
+command-account = Account settings
+command-logout = Log out
+
+## Registration
+create-account = Create an account
+back-to-login = Back to login
+error-username-empty = Choose a username
+error-username-taken = That username is already taken
+error-registration-rejected = The server refused the registration: { $reason }
+error-password-policy = The password does not meet every requirement
+rule-min-length = At least { $length } characters
+rule-uppercase = An uppercase letter
+rule-lowercase = A lowercase letter
+rule-digit = A digit
+rule-symbol = A symbol
+rule-not-common = Not a commonly used password
+strength-very-weak = Very weak
+strength-weak = Weak
+strength-fair = Fair
+strength-strong = Strong
+strength-very-strong = Very strong
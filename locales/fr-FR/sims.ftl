@@ -0,0 +1,133 @@
+## Connexion
+app-title = SIMS Système de gestion des stocks
+choose-profile = Choisir un profil
+server = Serveur
+username = Nom d'utilisateur
+password = Mot de passe
+remember-me = Se souvenir de moi
+remember-me-unavailable = Définissez SIMS_PASSPHRASE pour pouvoir rester connecté
+login = Se connecter
+logging-in = Connexion en cours...
+error-not-connected = Impossible de joindre le serveur
+error-session-expired = Votre connexion enregistrée a expiré, veuillez vous reconnecter
+error-server = Le serveur a refusé la requête : { $reason }
+
+## Onglets et navigation
+tab-shelves = Étagères
+tab-items = Articles
+tab-dashboard = Tableau de bord
+tab-move-hint = Cliquez sur l'onglet dont il doit prendre la place, Échap pour annuler
+back = Retour
+search-items = Rechercher des articles (Ctrl+F)
+
+## Étagères
+my-shelves = Mes étagères
+column-name = Nom
+column-slots = Nb d'emplacements
+column-occupancy = Occupation estimée
+column-percent-full = % rempli (est.)
+column-actions = Actions
+slot-count = Emplacements : { $count }
+slots-estimated = Les emplacements sont estimés : le serveur ne les fournit pas, chaque article est compté dans son propre emplacement de { $capacity } unités
+open = Ouvrir
+
+## Articles
+all-items = Tous les articles
+column-item = Article
+column-id = ID
+column-description = Description
+column-shelf = Étagère
+column-price = Prix
+column-stock = Stock
+viewing-shelf = Étagère { $shelf }
+invalid-shelf-tab = Onglet d'étagère invalide
+
+## Tableau de bord
+total-skus = Total des références
+total-units = Total des unités
+inventory-value = Valeur du stock
+shelf-utilisation = Utilisation estimée des étagères
+stock-distribution = Répartition du stock
+
+## Suggestions
+suggestions = Suggestions
+no-suggestions = Aucune suggestion, les niveaux de stock sont bons
+dismiss = Ignorer
+suggest-restock = Réapprovisionner l'article { $item } de l'étagère { $shelf } à { $target }
+suggest-consolidate = Regrouper les emplacements { $slots } de l'étagère { $shelf }, estimés à moitié vides
+
+## Modification
+nothing-to-edit = Rien à modifier
+editing-item = Modification de l'article { $item } de l'étagère { $shelf }
+editing-slot = Modification de l'emplacement { $slot } de l'étagère { $shelf }
+editing-shelf = Modification de l'étagère { $shelf }
+new-shelf = Nouvelle étagère
+field-shelf = Étagère
+field-name = Nom
+field-slots = Emplacements
+placeholder-shelf-name = Nom de l'étagère
+create = Créer
+error-min-slots = Votre étagère doit avoir au moins 1 emplacement
+error-shelf-empty = Un identifiant d'étagère est requis
+error-slots-natural = Le nombre d'emplacements doit être un entier naturel
+
+## Interface terminal
+tui-keys = ←/→ onglets  ↑/↓ sélection  Entrée ouvrir  n nouvelle étagère  r actualiser  w fermer l'onglet  q quitter
+tui-form-keys = Tab champ suivant  Entrée enregistrer  Échap annuler
+tui-saved = Enregistré
+tui-refreshed = Actualisé
+
+## Palette de commandes
+command-palette = Palette de commandes
+palette-placeholder = Saisir une commande, une étagère ou un article
+command-new = Nouvelle étagère
+command-refresh = Actualiser
+command-go-shelves = Aller aux étagères
+command-go-items = Aller aux articles
+command-go-dashboard = Aller au tableau de bord
+command-suggestions = Afficher les suggestions
+command-settings = Paramètres
+palette-shelf = Étagère { $shelf }
+palette-item = Article { $item } { $description } sur { $shelf }
+
+## Paramètres
+settings = Paramètres
+setting-theme = Thème
+setting-language = Langue
+theme-light = Clair
+theme-dark = Sombre
+theme-system = Suivre le système
+locale-system = Langue du système
+palettes-path = Les palettes personnalisées sont lues depuis { $path }
+palettes-unavailable = Les palettes personnalisées sont indisponibles sans répertoire personnel
+setting-scale = Échelle de l'interface
+scale-hint = Ctrl+= et Ctrl+- changent aussi l'échelle, Ctrl+0 la réinitialise
+
+## Compte
+account = Compte
+signed-in-as = Connecté en tant que { $username } sur { $server }
+confirm-password = Confirmer le mot de passe
+error-password-mismatch = Les nouveaux mots de passe ne correspondent pas
+switch-profile = Changer de profil
+logout = Se déconnecter
+command-account = Paramètres du compte
+command-logout = Se déconnecter
+
+## Inscription
+create-account = Créer un compte
+back-to-login = Retour à la connexion
+error-username-empty = Choisissez un nom d'utilisateur
+error-username-taken = Ce nom d'utilisateur est déjà pris
+error-registration-rejected = Le serveur a refusé l'inscription : { $reason }
+error-password-policy = Le mot de passe ne respecte pas toutes les exigences
+rule-min-length = Au moins { $length } caractères
+rule-uppercase = Une lettre majuscule
+rule-lowercase = Une lettre minuscule
+rule-digit = Un chiffre
+rule-symbol = Un symbole
+rule-not-common = Pas un mot de passe courant
+strength-very-weak = Très faible
+strength-weak = Faible
+strength-fair = Moyen
+strength-strong = Fort
+strength-very-strong = Très fort
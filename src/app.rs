@@ -0,0 +1,933 @@
+use std::collections::{HashMap, HashSet};
+use async_std::sync::Arc;
+use std::env::set_var;
+use iced::{Application, Command, Element, executor, Subscription, Theme, window};
+use async_std::sync::Mutex;
+use iced::futures::TryFutureExt;
+use iced::Length::{Fill};
+use iced::widget::{
+    self, Container, Text, text_input,
+};
+use iced::window::icon::Icon;
+use linked_hash_set::LinkedHashSet;
+use log::{debug, error, info};
+use std::future::IntoFuture;
+use std::time::Duration;
+
+use crate::{frontend, session, shortcuts, views};
+use crate::assets::logo_bytes;
+use crate::credentials::StoredCredential;
+use crate::frontend::{create_shelf, EditTarget, GetItemsResponse, LoginResult, read_items, read_shelves, SharedClient, ShelfSort, TabId};
+use crate::frontend::sims_ims_frontend::{ItemInfo, Items, ShelfInfo, Shelves};
+use crate::i18n::{LocaleChoice, set_locale, tr, tr_args};
+use crate::money::MoneyFormat;
+use crate::navigation::{reorder_tabs, TabHistory};
+use crate::session::Session;
+use crate::profiles::{load_profiles, Profile};
+use crate::palette::{CommandPalette, edit_input_id, login_input_id, palette_entries, palette_input_id, search_input_id};
+use crate::shortcuts::Shortcut;
+use crate::states::SimsClientState;
+use crate::suggestions::{generate_suggestions, Suggestion, Thresholds};
+use crate::themes::{CustomPalette, load_palettes, resolve_theme, system_prefers_dark, ThemeChoice, UI_SCALES, UiScale};
+use crate::ui_messages::Message;
+use crate::ui_messages::Message::{CloseShelf, OpenShelf, StartEditing, StopEditing, TabSelected, UpdateAll, UpdatedItems, UpdatedShelves, UpdateItems, UpdateShelves};
+use crate::views::Pane;
+
+// the window, only started when no subcommand is given
+pub(crate) fn run() -> iced::Result {
+    if !cfg!(macos) {
+        set_var("WGPU_BACKEND", "vulkan");
+    }
+    let session = Session::load();
+    ClientState::run(iced::Settings {
+        window: window::Settings {
+            size: session.window_size,
+            icon: match logo_bytes() {
+                None => None,
+                Some((bytes, width, height)) => Icon::from_rgba(bytes, width, height).ok(),
+            },
+            ..window::Settings::default()
+        },
+        // closing is intercepted so the session can be written out first
+        exit_on_close_request: false,
+        ..iced::Settings::with_flags(session)
+    })
+}
+
+// an explicit language also switches the separators, otherwise LC_MONETARY keeps deciding them
+fn money_format_for(locale_choice: &LocaleChoice) -> MoneyFormat {
+    match locale_choice {
+        LocaleChoice::System => MoneyFormat::from_env(),
+        LocaleChoice::Locale(tag) => MoneyFormat::from_env().with_locale(tag)
+    }
+}
+
+fn login_error_message(err: LoginResult) -> String {
+    match err {
+        LoginResult::ServerError(e) => tr_args("error-server", &[("reason", e.message().to_owned().into())]),
+        LoginResult::NotConnected => tr("error-not-connected"),
+        LoginResult::UsernameTaken => tr("error-username-taken"),
+        LoginResult::RegistrationRejected(reason) => tr_args("error-registration-rejected", &[("reason", reason.into())]),
+        LoginResult::SessionExpired => tr("error-session-expired")
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ClientState {
+    pub(crate) username: String,
+    pub(crate) state: SimsClientState,
+    pub(crate) rpc: SharedClient,
+    pub(crate) token: Option<String>,
+    pub(crate) tab_history: TabHistory,
+    pub(crate) pinned_tabs: HashSet<TabId>,
+    // picked with the tab's move button, the next tab clicked gives up its place to it
+    pub(crate) moving_tab: Option<TabId>,
+    pub(crate) tabs: LinkedHashSet<TabId>,
+    pub(crate) edit_item: Option<EditTarget>,
+    pub(crate) shelves: Vec<ShelfInfo>,
+    pub(crate) all_items: HashMap<String, Vec<ItemInfo>>,
+    pub(crate) shelf_sort: ShelfSort,
+    pub(crate) money_format: MoneyFormat,
+    pub(crate) palette: Option<CommandPalette>,
+    pub(crate) item_filter: String,
+    pub(crate) profile: Profile,
+    pub(crate) profiles: Vec<Profile>,
+    pub(crate) window_size: (u32, u32),
+    pub(crate) restored_session: Option<Session>,
+    pub(crate) should_exit: bool,
+    pub(crate) remember_me: bool,
+    // the credentials file is shared with the command line, so only a login this session saved or resumed is ours to delete
+    pub(crate) saved_login: bool,
+    pub(crate) split_tab: Option<TabId>,
+    // the split pane searches on its own, only the main filter is saved with the session
+    pub(crate) split_item_filter: String,
+    pub(crate) theme_choice: ThemeChoice,
+    pub(crate) locale_choice: LocaleChoice,
+    pub(crate) ui_scale: UiScale,
+    pub(crate) palettes: Vec<CustomPalette>,
+    pub(crate) system_dark: bool
+}
+
+impl ClientState {
+    // the server can create shelves but not items, so every tab offers a new shelf
+    pub(crate) fn new_edit_target(&self) -> EditTarget {
+        EditTarget::NewShelf {
+            shelf_name: "".to_owned(),
+            slots: "0".to_owned(),
+            error_message: None,
+        }
+    }
+
+    pub(crate) fn refresh_message(&self) -> Message {
+        ClientState::refresh_message_for(self.tab_history.current())
+    }
+
+    pub(crate) fn refresh_message_for(tab: &TabId) -> Message {
+        match tab {
+            TabId::AllShelves | TabId::Dashboard => UpdateAll,
+            TabId::AllItems => UpdateItems(None),
+            TabId::ShelfView(shelf_id) => UpdateItems(Some(shelf_id.clone())),
+        }
+    }
+
+    // the saved layout is only reapplied for the user who left it behind
+    fn restore_session(&mut self) {
+        let session = match self.restored_session.take() {
+            Some(s) if s.username == self.username => s,
+            _ => return
+        };
+
+        self.pinned_tabs = session.pinned_tabs.into_iter().collect();
+        for tab in session.tabs {
+            self.tabs.insert(tab);
+        }
+        if let Some(active) = session.active_tab {
+            if self.tabs.contains(&active) {
+                self.tab_history.visit(active);
+            }
+        }
+        self.shelf_sort = session.shelf_sort;
+        self.item_filter = session.item_filter;
+    }
+
+    // drops the server connection and every cached response so another account can log in cleanly
+    fn teardown_connection(&mut self) {
+        self.rpc = Arc::new(Mutex::new(None));
+        self.token = None;
+        self.state = SimsClientState::Unauthenticated {
+            password: String::new(),
+            error_message: None,
+        };
+        self.shelves.clear();
+        self.all_items.clear();
+        self.tabs.clear();
+        self.tabs.insert(TabId::AllShelves);
+        self.tabs.insert(TabId::AllItems);
+        self.tabs.insert(TabId::Dashboard);
+        self.pinned_tabs.clear();
+        self.tab_history = TabHistory::default();
+        self.moving_tab = None;
+        self.edit_item = None;
+        self.palette = None;
+        self.item_filter.clear();
+        self.remember_me = false;
+        self.saved_login = false;
+        self.split_tab = None;
+        self.split_item_filter.clear();
+    }
+
+    fn save_session(&self) {
+        let session = Session {
+            server: self.profile.server.clone(),
+            profile: Some(self.profile.name.clone()).filter(|n| !n.is_empty()),
+            username: self.username.clone(),
+            tabs: self.tabs.iter().cloned().collect(),
+            pinned_tabs: self.pinned_tabs.iter().cloned().collect(),
+            active_tab: Some(self.tab_history.current().clone()),
+            shelf_sort: self.shelf_sort,
+            item_filter: self.item_filter.clone(),
+            theme: Some(self.theme_choice.name()),
+            locale: self.locale_choice.tag(),
+            ui_scale: self.ui_scale.0,
+            window_size: self.window_size,
+            ..Session::default()
+        };
+
+        if let Err(e) = session.save() {
+            error!("Failed to save session: {}", e);
+        }
+    }
+
+    fn load_tab(&self, tab_id: TabId) -> Command<Message> {
+        match tab_id {
+            TabId::AllItems => {
+                Command::perform(read_items(Arc::clone(&self.rpc), None), UpdatedItems)
+            }
+            TabId::AllShelves | TabId::Dashboard => {
+                Command::perform(async {}, |_| Message::UpdateAll)
+            }
+            TabId::ShelfView(shelf_id) => {
+                Command::perform(read_items(Arc::clone(&self.rpc), Some(shelf_id)), UpdatedItems)
+            }
+        }
+    }
+
+    fn handle_shortcut(&mut self, shortcut: Shortcut) -> Command<Message> {
+        // focus and zoom work on every screen, including the login form
+        match shortcut {
+            Shortcut::FocusNext => return widget::focus_next(),
+            Shortcut::FocusPrevious => return widget::focus_previous(),
+            Shortcut::ZoomIn => return self.update(Message::UiScaleSelected(self.ui_scale.step(true))),
+            Shortcut::ZoomOut => return self.update(Message::UiScaleSelected(self.ui_scale.step(false))),
+            Shortcut::ZoomReset => return self.update(Message::UiScaleSelected(UiScale::default())),
+            _ => {}
+        }
+
+        match (&self.state, shortcut) {
+            (SimsClientState::DisplaySuggestions { .. }, Shortcut::Escape) => return self.update(Message::CloseSuggestions),
+            (SimsClientState::Settings, Shortcut::Escape) => return self.update(Message::CloseSettings),
+            (SimsClientState::Account, Shortcut::Escape) => return self.update(Message::CloseAccount),
+            (SimsClientState::Registering { .. }, Shortcut::Escape) => return self.update(Message::CloseRegistration),
+            (SimsClientState::InventoryView, _) => {}
+            _ => return Command::none()
+        }
+
+        if let Some(query) = self.palette.as_ref().map(|p| p.query.clone()) {
+            let entry_count = palette_entries(self, &query).len();
+            let palette = self.palette.as_mut().unwrap();
+            match shortcut {
+                Shortcut::Up => palette.selected = palette.selected.saturating_sub(1),
+                Shortcut::Down => palette.selected = (palette.selected + 1).min(entry_count.saturating_sub(1)),
+                Shortcut::TogglePalette | Shortcut::Escape => self.palette = None,
+                _ => {}
+            }
+            return Command::none();
+        }
+
+        if self.moving_tab.is_some() && shortcut == Shortcut::Escape {
+            self.moving_tab = None;
+            return Command::none();
+        }
+
+        if self.edit_item.is_some() && shortcut == Shortcut::Escape {
+            return self.update(StopEditing);
+        }
+
+        let current_tab = self.tab_history.current().clone();
+        match shortcut {
+            Shortcut::Back => self.update(Message::NavigateBack),
+            Shortcut::Forward => self.update(Message::NavigateForward),
+            Shortcut::TogglePalette => {
+                self.palette = Some(CommandPalette::default());
+                text_input::focus(palette_input_id())
+            }
+            Shortcut::NewObject => {
+                if self.edit_item.is_none() {
+                    self.update(StartEditing(self.new_edit_target()))
+                } else {
+                    Command::none()
+                }
+            }
+            Shortcut::CloseTab => self.update(CloseShelf(current_tab)),
+            Shortcut::NextTab | Shortcut::PreviousTab => {
+                let tabs = self.tabs.iter().cloned().collect::<Vec<TabId>>();
+                let position = tabs.iter().position(|t| *t == current_tab).unwrap_or(0);
+                let next = if shortcut == Shortcut::NextTab {
+                    (position + 1) % tabs.len()
+                } else {
+                    (position + tabs.len() - 1) % tabs.len()
+                };
+                self.update(TabSelected(tabs[next].clone()))
+            }
+            Shortcut::Refresh => self.update(self.refresh_message()),
+            Shortcut::FocusSearch => match current_tab {
+                TabId::AllItems | TabId::ShelfView(_) => text_input::focus(search_input_id()),
+                _ => Command::none()
+            },
+            _ => Command::none()
+        }
+    }
+}
+
+impl Application for ClientState {
+    type Executor = executor::Default;
+    type Message = Message;
+    type Theme = Theme;
+    type Flags = Session;
+    
+    fn new(session: Session) -> (Self, Command<Message>) {
+        let session_profile = session.profile.clone();
+        let session_server = session.server.clone();
+        let session_theme = session.theme.clone();
+        // the UI language is chosen before anything is drawn
+        let locale_choice = LocaleChoice::from_tag(session.locale.as_deref());
+        set_locale(locale_choice.resolve());
+        let mut new_client = ClientState {
+            username: session.username.clone(),
+            state: SimsClientState::Unauthenticated {
+                password: "".to_string(),
+                error_message: None,
+            },
+            rpc: Arc::new(Mutex::new(None)),
+            token: None,
+            tab_history: TabHistory::default(),
+            pinned_tabs: HashSet::new(),
+            moving_tab: None,
+            tabs: LinkedHashSet::new(),
+            edit_item: None,
+            shelves: Vec::new(),
+            all_items: HashMap::new(),
+            shelf_sort: ShelfSort::default(),
+            money_format: money_format_for(&locale_choice),
+            palette: None,
+            item_filter: String::new(),
+            profile: Profile::default(),
+            profiles: load_profiles(),
+            window_size: session.window_size,
+            restored_session: Some(session),
+            should_exit: false,
+            remember_me: false,
+            saved_login: false,
+            split_tab: None,
+            split_item_filter: String::new(),
+            theme_choice: ThemeChoice::Dark,
+            locale_choice,
+            ui_scale: UiScale(session.ui_scale.clamp(UI_SCALES[0], UI_SCALES[UI_SCALES.len() - 1])),
+            palettes: load_palettes(),
+            system_dark: system_prefers_dark()
+        };
+
+        new_client.tabs.insert(TabId::AllShelves);
+        new_client.tabs.insert(TabId::AllItems);
+        new_client.tabs.insert(TabId::Dashboard);
+        new_client.profile = match new_client.profiles.iter().find(|p| Some(&p.name) == session_profile.as_ref()) {
+            Some(profile) => profile.clone(),
+            None => Profile { server: session_server, username: new_client.username.clone(), ..Profile::default() }
+        };
+        // a theme picked in settings outlives the profile default
+        new_client.theme_choice = ThemeChoice::from_name(session_theme.as_ref().unwrap_or(&new_client.profile.theme));
+
+        let startup = match StoredCredential::load() {
+            Some(credential) => {
+                debug!("Resuming saved login for {}", credential.username);
+                new_client.username = credential.username.clone();
+                new_client.profile.server = credential.server.clone();
+                new_client.profile.tls = credential.tls.clone();
+                new_client.remember_me = true;
+                new_client.saved_login = true;
+                new_client.state = SimsClientState::Authenticating;
+                Command::perform(
+                    frontend::resume_session(Arc::clone(&new_client.rpc), credential.server, credential.tls, credential.username, credential.token),
+                    Message::Authenticated,
+                )
+            }
+            None => text_input::focus(login_input_id())
+        };
+        (new_client, startup)
+    }
+
+    fn title(&self) -> String {
+        tr("app-title")
+    }
+
+    fn update(&mut self, message: Self::Message) -> Command<Message> {
+        match message {
+            Message::CreateTarget => {
+                match &mut self.edit_item {
+                    None => {info!("Attempted to create shelf with no edit target"); Command::none()},
+                    Some(target) => match target{
+                        EditTarget::NewShelf {shelf_name, slots, error_message} => {
+                            if let Ok(count) = slots.parse::<u32>() {
+                                if count <= 0 {
+                                    error_message.insert(tr("error-min-slots"));
+                                    Command::none()
+                                }
+                                else {
+                                    println!("Creating shelf with name {}", shelf_name);
+                                    Command::batch([
+                                        Command::perform(create_shelf(Arc::clone(&self.rpc), shelf_name.clone(), count), |result| {match result {Err(e)=>debug!("{:?}", e), _=>{}}; UpdateShelves(None) }),
+                                        Command::perform(async{}, |_|{StopEditing})
+                                    ])
+                                }
+                            }else{
+                                error_message.insert(tr("error-slots-natural"));
+                                Command::none()
+                            }
+                        },
+                        _ => Command::none()
+                    }
+                }
+            }
+            Message::ShelfSlotCountInputChanged(ref c) => {
+                match &mut self.edit_item {
+                    None => info!("Received {:?} when not editing anything", message),
+                    Some(target)=> match target {
+                        EditTarget::NewShelf { ref mut slots, .. } => {
+                            *slots = c.clone()
+                        },
+                        _ => info!("Received message {:?} but current EditTarget is unsupported", message)
+                    }
+                };
+                Command::none()
+            }
+            Message::CreateObjectNameInputChanged(ref s) => {
+                match &mut self.edit_item {
+                    None => info!("Received {:?} when not editing anything", message),
+                    Some(target)=> match target {
+                        EditTarget::NewShelf { ref mut shelf_name, .. } => {
+                            *shelf_name = s.clone();
+                        },
+                        _ => info!("Received message {:?} but current EditTarget is unsupported", message)
+                    }
+                };
+                Command::none()
+            }
+            Message::UpdateItems(shelf) => {
+                Command::perform(read_items(Arc::clone(&self.rpc), shelf), Message::UpdatedItems)
+            }
+            // replies from a connection that has since been torn down are dropped
+            Message::UpdatedItems(_) | UpdatedShelves(_) if self.token.is_none() => Command::none(),
+            Message::UpdatedItems(result) => match result {
+                Ok(items) => {
+                    debug!("{:?}", items);
+                    match items {
+                        GetItemsResponse::ShelfItems(shelf_id, items) => {
+                            self.all_items.insert(shelf_id, items.items);
+                        },
+                        GetItemsResponse::AllItems(items) => {
+                            self.all_items.clear();
+                            self.all_items = items.items.into_iter().map(|i|(i.shelf_id.clone(), i)).fold(HashMap::new(), |mut h, v|{
+                                match h.get_mut(&v.0) {
+                                    Some(l)=> l.push(v.1),
+                                    None => {h.insert(v.0, vec![v.1]);}
+                                };
+                                h
+                            });
+                        }
+                    }
+                    Command::none()
+                },
+                Err(e) => {
+                    debug!("Items update failed: {:?}", e);
+                     Command::none()
+                }
+
+            },
+            Message::UpdateAll => {
+                Command::batch([
+                    Command::perform(read_shelves(Arc::clone(&self.rpc), None), UpdatedShelves),
+                    Command::perform(read_items(Arc::clone(&self.rpc), None), UpdatedItems)
+                ])
+            }
+            Message::UpdateShelves(shelf_id) => {
+                Command::perform(frontend::read_shelves(Arc::clone(&self.rpc), shelf_id), UpdatedShelves)
+            }
+            Message::ServerInputChanged(s) => {
+                if let SimsClientState::Unauthenticated { .. } | SimsClientState::Registering { .. } = self.state {
+                    self.profile.server = s;
+                    self.rpc = Arc::new(Mutex::new(None));
+                }
+
+                Command::none()
+            }
+            Message::ProfileSelected(name) => {
+                if let SimsClientState::Unauthenticated { .. } = self.state {
+                    if let Some(profile) = self.profiles.iter().find(|p| p.name == name) {
+                        self.profile = profile.clone();
+                        self.username = profile.username.clone();
+                        self.theme_choice = ThemeChoice::from_name(&profile.theme);
+                        // a channel opened for the previous profile must not carry over to this one
+                        self.rpc = Arc::new(Mutex::new(None));
+                    }
+                }
+
+                Command::none()
+            }
+            // logging out and picking the profile in one go leaves only the password to type
+            Message::SwitchProfile(name) => {
+                let focus = self.update(Message::Logout);
+                self.update(Message::ProfileSelected(name));
+                focus
+            }
+            Message::Logout => {
+                if self.token.is_some() {
+                    self.save_session();
+                }
+                // only logging out on purpose deletes the saved login, a failed or expired one is left for the next attempt
+                if self.saved_login {
+                    StoredCredential::forget();
+                }
+                self.teardown_connection();
+                text_input::focus(login_input_id())
+            }
+            Message::ShowAccount => {
+                if let SimsClientState::InventoryView = self.state {
+                    self.state = SimsClientState::Account;
+                }
+                Command::none()
+            }
+            Message::CloseAccount => {
+                if let SimsClientState::Account = self.state {
+                    self.state = SimsClientState::InventoryView;
+                }
+                Command::none()
+            }
+            Message::ConfirmPasswordChanged(s) => {
+                if let SimsClientState::Registering { ref mut confirm_password, .. } = self.state {
+                    *confirm_password = s
+                }
+                Command::none()
+            }
+            Message::RememberMeToggled(remember) => {
+                if let SimsClientState::Unauthenticated { .. } = self.state {
+                    self.remember_me = remember
+                }
+
+                Command::none()
+            }
+            Message::WindowResized(width, height) => {
+                self.window_size = (width, height);
+                Command::none()
+            }
+            Message::CloseRequested => {
+                // only an authenticated session has a layout worth keeping
+                if self.token.is_some() {
+                    self.save_session();
+                }
+                self.should_exit = true;
+                Command::none()
+            }
+            Message::UsernameInputChanged(s) => {
+                if let SimsClientState::Unauthenticated { .. } | SimsClientState::Registering { .. } = self.state {
+                    self.username = s
+                }
+
+                Command::none()
+            }
+            Message::PasswordInputChanged(s) => {
+                if let SimsClientState::Unauthenticated {
+                    ref mut password, ..
+                } | SimsClientState::Registering {
+                    ref mut password, ..
+                } = self.state
+                {
+                    password.clear();
+                    password.push_str(&s)
+                }
+
+                Command::none()
+            }
+            Message::LoginButtonClicked => {
+                if let SimsClientState::Unauthenticated { ref password, .. } = self.state {
+                    let client_ = Arc::clone(&self.rpc);
+                    let ret = Command::perform(
+                        frontend::login(
+                            client_,
+                            self.profile.server.clone(),
+                            self.profile.tls.clone(),
+                            self.username.to_owned(),
+                            password.to_owned(),
+                        ),
+                        Message::Authenticated,
+                    );
+                    self.state = SimsClientState::Authenticating;
+                    ret
+                } else {
+                    // login button clicked in non-login screen
+                    Command::none()
+                }
+            },
+            Message::ShowRegistration => {
+                if let SimsClientState::Unauthenticated { .. } = self.state {
+                    self.state = SimsClientState::Registering {
+                        password: String::new(),
+                        confirm_password: String::new(),
+                        error_message: None,
+                    };
+                }
+                text_input::focus(login_input_id())
+            }
+            Message::CloseRegistration => {
+                if let SimsClientState::Registering { .. } = self.state {
+                    self.state = SimsClientState::Unauthenticated {
+                        password: String::new(),
+                        error_message: None,
+                    };
+                }
+                text_input::focus(login_input_id())
+            }
+            Message::RegisterButtonClicked => {
+                if let SimsClientState::Registering { ref password, ref confirm_password, ref mut error_message } = self.state {
+                    // the button is disabled until these pass, Enter in a field can still get here
+                    if self.username.trim().is_empty() {
+                        *error_message = Some(tr("error-username-empty"));
+                        return Command::none();
+                    }
+                    if !self.profile.password_policy.is_satisfied(password) {
+                        *error_message = Some(tr("error-password-policy"));
+                        return Command::none();
+                    }
+                    if password != confirm_password {
+                        *error_message = Some(tr("error-password-mismatch"));
+                        return Command::none();
+                    }
+
+                    let client_ = Arc::clone(&self.rpc);
+                    let ret = Command::perform(
+                        frontend::register_and_login(
+                            client_,
+                            self.profile.server.clone(),
+                            self.profile.tls.clone(),
+                            self.username.to_owned(),
+                            password.to_owned(),
+                        ),
+                        Message::Registered,
+                    );
+                    self.state = SimsClientState::Authenticating;
+                    ret
+                } else {
+                    // register button clicked outside the registration screen
+                    Command::none()
+                }
+            },
+            Message::Registered(result) => match result {
+                Ok(token) => self.update(Message::Authenticated(Ok(token))),
+                Err(err) => {
+                    debug!("Failed to register {:?}", err);
+                    self.state = SimsClientState::Registering {
+                        password: String::new(),
+                        confirm_password: String::new(),
+                        error_message: Some(login_error_message(err)),
+                    };
+                    Command::none()
+                }
+            },
+            Message::Authenticated(authentication_result) => {
+                match authentication_result {
+                    Ok(response) => {
+                        self.token = Some(response.token);
+
+                        if self.remember_me {
+                            let credential = StoredCredential {
+                                server: self.profile.server.clone(),
+                                tls: self.profile.tls.clone(),
+                                username: self.username.clone(),
+                                token: self.token.clone().unwrap(),
+                            };
+                            match credential.save() {
+                                Ok(()) => self.saved_login = true,
+                                Err(e) => error!("Failed to remember login: {}", e)
+                            }
+                        }
+
+                        if matches!(self.state, SimsClientState::Authenticating { .. }) {
+                            self.state = SimsClientState::InventoryView;
+                            self.restore_session();
+                            self.save_session();
+                            Command::batch([
+                                Command::perform(frontend::read_shelves(Arc::clone(&self.rpc), None), UpdatedShelves),
+                                self.load_tab(self.tab_history.current().clone())
+                            ])
+                        }else {
+                            Command::none()
+                        }
+                    }
+                    Err(err) => {
+                        debug!("Failed to log in {:?}", err);
+                        self.username = String::new();
+                        self.state = SimsClientState::Unauthenticated {
+                            password: String::new(),
+                            error_message: Some(login_error_message(err)),
+                        };
+                        Command::none()
+                    }
+                }
+            }
+            Message::TabSelected(tab_id) => {
+                debug!("Selected tab {:?}", tab_id);
+                self.tab_history.visit(tab_id.clone());
+                self.load_tab(tab_id)
+            }
+            Message::NavigateBack => match self.tab_history.back() {
+                Some(tab_id) => self.load_tab(tab_id),
+                None => Command::none()
+            },
+            Message::NavigateForward => match self.tab_history.forward() {
+                Some(tab_id) => self.load_tab(tab_id),
+                None => Command::none()
+            },
+            Message::CloseShelf(tab_id) => {
+                match tab_id {
+                    TabId::AllShelves | TabId::AllItems | TabId::Dashboard => {} // can't delete these tabs
+                    TabId::ShelfView(_) if self.pinned_tabs.contains(&tab_id) => {} // unpin before closing
+                    TabId::ShelfView(_) => {
+                        self.tabs.remove(&tab_id);
+                        self.tab_history.remove(&tab_id);
+                        if self.split_tab.as_ref() == Some(&tab_id) {
+                            self.split_tab = None;
+                            self.split_item_filter.clear();
+                        }
+                    }
+                }
+
+                Command::none()
+            }
+            Message::ToggleSplit => {
+                match self.split_tab.take() {
+                    Some(_) => {
+                        self.split_item_filter.clear();
+                        Command::none()
+                    }
+                    None => {
+                        // open the most recently visited other tab beside the current one
+                        let current = self.tab_history.current().clone();
+                        let tab = self.tabs.iter().rev().find(|t| **t != current).cloned().unwrap_or(TabId::AllItems);
+                        self.split_tab = Some(tab.clone());
+                        self.load_tab(tab)
+                    }
+                }
+            }
+            Message::SplitTabSelected(tab_id) => {
+                self.split_tab = Some(tab_id.clone());
+                self.load_tab(tab_id)
+            }
+            Message::TogglePinTab(tab_id) => {
+                if !self.pinned_tabs.remove(&tab_id) {
+                    self.pinned_tabs.insert(tab_id.clone());
+                }
+                // re-sort so pinned tabs lead the bar
+                self.tabs = reorder_tabs(&self.tabs, &self.pinned_tabs, &tab_id, &tab_id);
+                Command::none()
+            }
+            Message::TabMoveStarted(tab_id) => {
+                self.moving_tab = Some(tab_id);
+                Command::none()
+            }
+            Message::TabMovedTo(target) => {
+                if let Some(moving) = self.moving_tab.take() {
+                    self.tabs = reorder_tabs(&self.tabs, &self.pinned_tabs, &moving, &target);
+                }
+                Command::none()
+            }
+            Message::OpenShelf(tab_id) => {
+                if !self.tabs.contains(&tab_id) {
+                    self.tabs.insert(tab_id.clone());
+                }
+
+                Command::perform(async {tab_id}, TabSelected)
+            }
+            StopEditing => {
+                self.edit_item = None;
+                Command::none()
+            }
+            StartEditing(target) => {
+                self.edit_item = Some(target);
+                text_input::focus(edit_input_id())
+            }
+            UpdatedShelves(shelves) => {
+                match shelves {
+                    Ok(s) => {self.shelves = s.shelves;}
+                    Err(e) => println!("{:?}", e)
+                }
+                Command::none()
+            }
+            Message::ShelfSortChanged(column) => {
+                if self.shelf_sort.column == column {
+                    self.shelf_sort.descending = !self.shelf_sort.descending;
+                } else {
+                    self.shelf_sort = ShelfSort { column, descending: false };
+                }
+                Command::none()
+            }
+            Message::Shortcut(shortcut) => self.handle_shortcut(shortcut),
+            Message::PaletteQueryChanged(query) => {
+                if let Some(palette) = self.palette.as_mut() {
+                    palette.query = query;
+                    palette.selected = 0;
+                }
+                Command::none()
+            }
+            Message::PaletteSelected(index) => {
+                let entry = match self.palette.take() {
+                    Some(palette) => palette_entries(self, &palette.query).into_iter().nth(index),
+                    None => None
+                };
+                match entry {
+                    Some(entry) => self.update(entry.message),
+                    None => Command::none()
+                }
+            }
+            Message::ItemFilterChanged(pane, filter) => {
+                match pane {
+                    Pane::Primary => self.item_filter = filter,
+                    Pane::Secondary => self.split_item_filter = filter
+                }
+                Command::none()
+            }
+            Message::ShowSuggestions => {
+                if let SimsClientState::InventoryView = self.state {
+                    self.state = SimsClientState::DisplaySuggestions {
+                        suggestions: generate_suggestions(&self.shelves, &self.all_items, Thresholds::default())
+                    };
+                }
+                Command::none()
+            }
+            Message::CloseSuggestions => {
+                if let SimsClientState::DisplaySuggestions { .. } = self.state {
+                    self.state = SimsClientState::InventoryView;
+                }
+                Command::none()
+            }
+            Message::ShowSettings => {
+                if let SimsClientState::InventoryView = self.state {
+                    self.palettes = load_palettes();
+                    self.state = SimsClientState::Settings;
+                }
+                Command::none()
+            }
+            Message::CloseSettings => {
+                if let SimsClientState::Settings = self.state {
+                    self.state = SimsClientState::InventoryView;
+                }
+                Command::none()
+            }
+            Message::ThemeSelected(choice) => {
+                self.theme_choice = choice;
+                self.save_session();
+                Command::none()
+            }
+            Message::LocaleSelected(choice) => {
+                set_locale(choice.resolve());
+                self.money_format = money_format_for(&choice);
+                self.locale_choice = choice;
+                self.save_session();
+                Command::none()
+            }
+            Message::UiScaleSelected(scale) => {
+                self.ui_scale = scale;
+                if self.token.is_some() {
+                    self.save_session();
+                }
+                Command::none()
+            }
+            Message::CheckSystemTheme => Command::perform(async { system_prefers_dark() }, Message::SystemThemeDetected),
+            Message::SystemThemeDetected(dark) => {
+                self.system_dark = dark;
+                Command::none()
+            }
+            Message::SuggestionDismissed(index) => {
+                if let SimsClientState::DisplaySuggestions { ref mut suggestions, .. } = self.state {
+                    if index < suggestions.len() {
+                        suggestions.remove(index);
+                    }
+                }
+                Command::none()
+            }
+            // the server can't change stock or slots, so either suggestion is acted on from the shelf
+            Message::SuggestionOpened(index) => {
+                let shelf_id = match self.state {
+                    SimsClientState::DisplaySuggestions { ref suggestions } if index < suggestions.len() => match &suggestions[index] {
+                        Suggestion::Restock { item, .. } => item.shelf_id.clone(),
+                        Suggestion::Consolidate { shelf_id, .. } => shelf_id.clone()
+                    },
+                    _ => return Command::none()
+                };
+
+                self.state = SimsClientState::InventoryView;
+                Command::perform(async {TabId::ShelfView(shelf_id)}, OpenShelf)
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Self::Message> {
+        match &self.state {
+            SimsClientState::Unauthenticated {
+                password,
+                error_message,
+            } => views::unauthenticated_view(self, password, error_message),
+            SimsClientState::Registering { password, confirm_password, error_message } => {
+                views::registration_view(self, password, confirm_password, error_message)
+            }
+            SimsClientState::Authenticating { .. } => Container::new(Text::new(tr("logging-in")))
+                .width(Fill)
+                .height(Fill)
+                .center_x()
+                .center_y()
+                .into(),
+            SimsClientState::InventoryView => views::inventory_view(self),
+            SimsClientState::DisplaySuggestions { suggestions } => views::suggestions_view(suggestions),
+            SimsClientState::Settings => views::settings_view(self),
+            SimsClientState::Account => views::account_view(self),
+            _ => Container::new(Text::new(format!(
+                "Placeholder for state: {:?}",
+                self.state
+            )))
+            .width(Fill)
+            .height(Fill)
+            .center_x()
+            .center_y()
+            .into(),
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let system_theme = match self.theme_choice {
+            // the OS gives no change notification, so poll while following it
+            ThemeChoice::System => iced::time::every(Duration::from_secs(5)).map(|_| Message::CheckSystemTheme),
+            _ => Subscription::none()
+        };
+        Subscription::batch([shortcuts::subscription(), session::subscription(), system_theme])
+    }
+
+    fn should_exit(&self) -> bool {
+        self.should_exit
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.ui_scale.factor()
+    }
+
+    fn theme(&self) -> Self::Theme {
+        resolve_theme(&self.theme_choice, &self.palettes, self.system_dark)
+    }
+}
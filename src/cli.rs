@@ -0,0 +1,459 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use async_std::sync::Arc;
+use clap::{Parser, Subcommand};
+use async_std::sync::Mutex;
+use crate::frontend::{create_shelf, DEFAULT_SLOT_CAPACITY, GetItemsResponse, login, LoginResult, read_item, read_items, read_shelves, RpcCallResult, SharedClient, shelf_slots};
+use crate::frontend::sims_ims_frontend::ItemInfo;
+use crate::credentials::StoredCredential;
+use crate::doctor::run_doctor;
+use crate::money::MoneyFormat;
+use crate::output::{CommandOutput, IdentityRecord, ItemRecord, OutputFormat, ShelfRecord, SlotRecord, TokenRecord};
+use crate::profiles::{load_profiles, Profile, TlsSettings};
+use crate::script::run_script;
+use crate::session::Session;
+use crate::shell::run_shell;
+use crate::watch::{run_watch, WatchOptions};
+#[cfg(feature = "tui")]
+use crate::tui::run_tui;
+
+/// Shelf inventory management client. Starts the GUI when no command is given.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub(crate) struct Cli {
+    /// Server address, overrides the profile and the last used server
+    #[arg(long, global = true)]
+    server: Option<String>,
+
+    /// Profile from profiles.toml to take the server, username and TLS settings from
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    #[arg(long, short, global = true)]
+    username: Option<String>,
+
+    #[arg(long, short, global = true, env = "SIMS_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Read the password from the first line of standard input
+    #[arg(long, global = true, conflicts_with = "password")]
+    password_stdin: bool,
+
+    /// Session token from an earlier `login`, used instead of the password
+    #[arg(long, global = true, env = "SIMS_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// File holding a session token, used instead of the password
+    #[arg(long, global = true, conflicts_with = "token")]
+    token_file: Option<PathBuf>,
+
+    /// How shelves, items and slots are printed, see the README for the field names
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+    /// Check the credentials, print a session token and cache it for later commands
+    Login,
+    /// Forget the cached session token
+    Logout,
+    /// Show the username, server and token expiry of the cached login
+    Whoami,
+    /// Check the connection, TLS, every RPC and the login step by step and print a report
+    Doctor {
+        /// Also call SignUp and CreateShelf, which a server may act on even with empty fields
+        #[arg(long)]
+        probe_writes: bool
+    },
+    /// List and create shelves
+    #[command(subcommand)]
+    Shelves(ShelfCommand),
+    /// List and inspect items
+    #[command(subcommand)]
+    Items(ItemCommand),
+    /// Interactive prompt that keeps one session open
+    Shell,
+    /// Full-screen terminal interface with the GUI's shelf and item tabs
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Run shelf and item commands from a script, one per line
+    Run {
+        /// Script to run, standard input when missing or -
+        file: Option<PathBuf>,
+        /// Check every line against the current inventory without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Keep going after a line fails instead of stopping
+        #[arg(long)]
+        continue_on_error: bool
+    },
+    /// Poll shelves and items and print every change as an event
+    Watch {
+        /// Only watch this shelf and its items
+        #[arg(long)]
+        shelf: Option<String>,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+        /// Alert when an item's stock drops below this
+        #[arg(long)]
+        low_stock: Option<u32>,
+        /// Stop after this many polls instead of running until interrupted
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        count: Option<u64>,
+        /// Exit with code 7 as soon as an alert is raised
+        #[arg(long, requires = "low_stock")]
+        exit_on_alert: bool
+    }
+}
+
+impl Command {
+    pub(crate) fn is_scriptable(&self) -> bool {
+        matches!(self, Command::Shelves(_) | Command::Items(_))
+    }
+
+    pub(crate) fn is_mutation(&self) -> bool {
+        matches!(self, Command::Shelves(ShelfCommand::Create { .. }))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ShelfCommand {
+    /// List every shelf
+    List,
+    /// Create a shelf
+    Create {
+        shelf_id: String,
+        #[arg(long, default_value_t = 1)]
+        slots: u32
+    },
+    /// Show how full each slot of a shelf is
+    Slots {
+        shelf_id: String
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ItemCommand {
+    /// List items, optionally only the ones on one shelf
+    List {
+        #[arg(long)]
+        shelf: Option<String>
+    },
+    /// Show a single item
+    Get {
+        item_id: u32
+    }
+}
+
+// exit codes are documented in the README, 2 is left to clap for usage errors
+#[derive(Debug, Clone)]
+pub(crate) enum CliError {
+    Auth(String),
+    NotFound(String),
+    Connection(String),
+    Validation(String),
+    Server(String),
+    Output(String),
+    Alert(String)
+}
+
+impl CliError {
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Server(_) | CliError::Output(_) => 1,
+            CliError::Auth(_) => 3,
+            CliError::NotFound(_) => 4,
+            CliError::Connection(_) => 5,
+            CliError::Validation(_) => 6,
+            CliError::Alert(_) => 7
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Auth(reason) => write!(f, "authentication failed: {}", reason),
+            CliError::NotFound(what) => write!(f, "not found: {}", what),
+            CliError::Connection(reason) => write!(f, "could not reach the server: {}", reason),
+            CliError::Validation(reason) => write!(f, "invalid input: {}", reason),
+            CliError::Server(reason) => write!(f, "server error: {}", reason),
+            CliError::Output(reason) => write!(f, "could not write output: {}", reason),
+            CliError::Alert(reason) => write!(f, "alert: {}", reason)
+        }
+    }
+}
+
+impl From<RpcCallResult> for CliError {
+    fn from(result: RpcCallResult) -> Self {
+        match result {
+            RpcCallResult::NotConnected => CliError::Connection("not connected".to_owned()),
+            RpcCallResult::NotFound(what) => CliError::NotFound(what),
+            RpcCallResult::Denied(reason) => CliError::Auth(reason),
+            RpcCallResult::CallFailed(reason) => CliError::Server(reason)
+        }
+    }
+}
+
+impl From<LoginResult> for CliError {
+    fn from(result: LoginResult) -> Self {
+        match result {
+            LoginResult::NotConnected => CliError::Connection("no response".to_owned()),
+            LoginResult::ServerError(status) => match status.code() {
+                tonic::Code::Unavailable => CliError::Connection(status.message().to_owned()),
+                tonic::Code::Unauthenticated | tonic::Code::PermissionDenied | tonic::Code::NotFound => CliError::Auth(status.message().to_owned()),
+                _ => CliError::Server(status.to_string())
+            },
+            LoginResult::SessionExpired => CliError::Auth("session expired".to_owned()),
+            #[cfg(feature = "gui")]
+            LoginResult::UsernameTaken => CliError::Validation("username taken".to_owned()),
+            #[cfg(feature = "gui")]
+            LoginResult::RegistrationRejected(reason) => CliError::Validation(reason)
+        }
+    }
+}
+
+pub(crate) struct Connection {
+    pub(crate) rpc: SharedClient,
+    pub(crate) server: String,
+    pub(crate) tls: TlsSettings,
+    pub(crate) username: String,
+    pub(crate) token: String
+}
+
+// same order the GUI uses: an explicit profile, then the one used last, then the last server typed in
+pub(crate) fn resolve_profile(cli: &Cli) -> Result<Profile, CliError> {
+    let session = Session::load();
+    let profiles = load_profiles();
+    let wanted = cli.profile.clone().or(session.profile);
+
+    let mut profile = match wanted {
+        Some(name) => match profiles.into_iter().find(|p| p.name == name) {
+            Some(profile) => profile,
+            None if cli.profile.is_some() => return Err(CliError::Validation(format!("no profile named {}", name))),
+            None => Profile { server: session.server, username: session.username, ..Profile::default() }
+        },
+        None => Profile { server: session.server, username: session.username, ..Profile::default() }
+    };
+
+    if let Some(server) = &cli.server {
+        profile.server = server.clone();
+    }
+    if let Some(username) = &cli.username {
+        profile.username = username.clone();
+    }
+    Ok(profile)
+}
+
+fn read_token_file(path: &Path) -> Result<String, CliError> {
+    let token = fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => CliError::NotFound(format!("token file {}", path.display())),
+        _ => CliError::Validation(format!("could not read the token file: {}", e))
+    })?;
+    Ok(token.trim().to_owned())
+}
+
+fn read_password_stdin() -> Result<String, CliError> {
+    let mut password = String::new();
+    io::stdin().read_line(&mut password).map_err(|e| CliError::Validation(format!("could not read the password: {}", e)))?;
+    Ok(password.trim_end_matches(&['\r', '\n'][..]).to_owned())
+}
+
+pub(crate) enum Credential {
+    Token(String),
+    Password(String)
+}
+
+// a token or password given in any form wins, the cached login is only the fallback and also decides the server it was for
+pub(crate) fn resolve_credential(cli: &Cli, profile: &mut Profile) -> Result<Option<Credential>, CliError> {
+    if let Some(token) = &cli.token {
+        return Ok(Some(Credential::Token(token.clone())));
+    }
+    if let Some(path) = &cli.token_file {
+        return Ok(Some(Credential::Token(read_token_file(path)?)));
+    }
+    if let Some(password) = &cli.password {
+        return Ok(Some(Credential::Password(password.clone())));
+    }
+    if cli.password_stdin {
+        return Ok(Some(Credential::Password(read_password_stdin()?)));
+    }
+
+    // the cache belongs to one server, so it only applies when no other one was asked for
+    let explicit_server = cli.server.is_some() || cli.profile.is_some();
+    let cached = StoredCredential::load().filter(|c| {
+        (!explicit_server || c.server == profile.server) && cli.username.as_ref().map(|u| u == &c.username).unwrap_or(true)
+    });
+    Ok(cached.map(|cached| {
+        profile.server = cached.server;
+        profile.tls = cached.tls;
+        profile.username = cached.username;
+        Credential::Token(cached.token)
+    }))
+}
+
+async fn authenticate(cli: &Cli) -> Result<Connection, CliError> {
+    let mut profile = resolve_profile(cli)?;
+    let credential = resolve_credential(cli, &mut profile)?
+        .ok_or_else(|| CliError::Auth("no password or token given and no cached login, run login first".to_owned()))?;
+
+    if profile.username.is_empty() {
+        return Err(CliError::Auth("no username given, pass --username or pick a --profile".to_owned()));
+    }
+
+    let rpc = Arc::new(Mutex::new(None));
+    let token = match credential {
+        Credential::Token(token) => {
+            let mut client = crate::frontend::connect(profile.server.clone(), profile.tls.clone()).await?;
+            client.use_token(&profile.username, &token);
+            let _ = rpc.lock().await.insert(client);
+            token
+        }
+        Credential::Password(password) => login(rpc.clone(), profile.server.clone(), profile.tls.clone(), profile.username.clone(), password).await?.token
+    };
+
+    Ok(Connection { rpc, server: profile.server, tls: profile.tls, username: profile.username, token })
+}
+
+// the token goes where the GUI keeps its remembered login, so either one can pick up the other's session
+async fn cache_login(connection: &Connection) -> Result<(), CliError> {
+    // a token from the command line hasn't been used yet, check it before keeping it
+    read_shelves(connection.rpc.clone(), None).await?;
+    StoredCredential {
+        server: connection.server.clone(),
+        tls: connection.tls.clone(),
+        username: connection.username.clone(),
+        token: connection.token.clone()
+    }.save().map_err(|e| CliError::Output(format!("could not cache the login: {}", e)))
+}
+
+fn token_output(connection: &Connection) -> CommandOutput {
+    CommandOutput::Token(vec![TokenRecord { username: connection.username.clone(), server: connection.server.clone(), token: connection.token.clone() }])
+}
+
+fn whoami() -> Result<CommandOutput, CliError> {
+    let cached = StoredCredential::load().ok_or_else(|| CliError::Auth("not logged in".to_owned()))?;
+    let expires = cached.expires_at().map(|t| humantime::format_rfc3339_seconds(t).to_string());
+    Ok(CommandOutput::Identity(vec![IdentityRecord { username: cached.username, server: cached.server, expires }]))
+}
+
+// the server has no logout call, so only the cached copy can be dropped
+fn logout() -> CommandOutput {
+    match StoredCredential::load() {
+        Some(cached) => {
+            StoredCredential::forget();
+            CommandOutput::Message(format!("Removed the cached login for {} on {}. The token itself stays valid until it expires.", cached.username, cached.server))
+        }
+        None => CommandOutput::Message("Not logged in".to_owned())
+    }
+}
+
+pub(crate) async fn run(cli: Cli) -> Result<(), CliError> {
+    let command = match &cli.command {
+        Some(command) => command,
+        None => return Ok(())
+    };
+    let money_format = MoneyFormat::from_env();
+
+    // nothing to authenticate for these, and an expired cache must not stop them
+    match command {
+        Command::Logout => return logout().print(cli.output, &money_format),
+        Command::Whoami => return whoami()?.print(cli.output, &money_format),
+        Command::Doctor { probe_writes } => return run_doctor(&cli, *probe_writes, cli.output, &money_format).await,
+        _ => {}
+    }
+
+    let reads_stdin = matches!(command, Command::Run { file, .. } if file.as_deref().map(|f| f == Path::new("-")).unwrap_or(true));
+    if cli.password_stdin && reads_stdin {
+        return Err(CliError::Validation("--password-stdin can't be used with a script on standard input".to_owned()));
+    }
+
+    let connection = authenticate(&cli).await?;
+    match command {
+        Command::Login => {
+            cache_login(&connection).await?;
+            token_output(&connection).print(cli.output, &money_format)
+        }
+        Command::Shell => run_shell(&connection, cli.output, &money_format).await,
+        #[cfg(feature = "tui")]
+        Command::Tui => run_tui(&connection, &money_format).await,
+        Command::Watch { shelf, interval, low_stock, count, exit_on_alert } => {
+            let options = WatchOptions { shelf_id: shelf.as_ref(), interval: Duration::from_secs(*interval), low_stock: *low_stock, count: *count, exit_on_alert: *exit_on_alert };
+            run_watch(&connection, options, cli.output, &money_format).await
+        }
+        Command::Run { file, dry_run, continue_on_error } => run_script(&connection, file.as_deref(), *dry_run, *continue_on_error, cli.output, &money_format).await,
+        command => execute(&connection, command).await?.print(cli.output, &money_format)
+    }
+}
+
+// runs one command and hands back what it produced, printing is left to the caller
+pub(crate) async fn execute(connection: &Connection, command: &Command) -> Result<CommandOutput, CliError> {
+    Ok(match command {
+        Command::Login => token_output(connection),
+        Command::Logout => logout(),
+        Command::Whoami => whoami()?,
+        Command::Run { .. } => return Err(CliError::Validation("scripts can't be started from here".to_owned())),
+        Command::Shelves(ShelfCommand::List) => {
+            let shelves = read_shelves(connection.rpc.clone(), None).await?;
+            CommandOutput::Shelves(shelves.shelves.iter().map(ShelfRecord::from).collect())
+        }
+        Command::Shelves(ShelfCommand::Create { shelf_id, slots }) => {
+            check_new_shelf(shelf_id, *slots)?;
+            create_shelf(connection.rpc.clone(), shelf_id.clone(), *slots).await?;
+            CommandOutput::Message(format!("Created shelf {}", shelf_id))
+        }
+        Command::Shelves(ShelfCommand::Slots { shelf_id }) => {
+            let shelves = read_shelves(connection.rpc.clone(), Some(shelf_id.clone())).await?;
+            let shelf = shelves.shelves.into_iter().find(|s| &s.shelf_id == shelf_id)
+                .ok_or_else(|| CliError::NotFound(format!("shelf {}", shelf_id)))?;
+            let items = list_items(connection, Some(shelf_id.clone())).await?;
+            CommandOutput::Slots(shelf_slots(&shelf, &items, DEFAULT_SLOT_CAPACITY).iter()
+                .map(|slot| SlotRecord::new(shelf_id, slot))
+                .collect())
+        }
+        Command::Items(ItemCommand::List { shelf }) => {
+            let items = list_items(connection, shelf.clone()).await?;
+            CommandOutput::Items(items.iter().map(ItemRecord::from).collect())
+        }
+        Command::Items(ItemCommand::Get { item_id }) => {
+            let item = fetch_item(connection, *item_id).await?;
+            CommandOutput::Items(vec![ItemRecord::from(&item)])
+        }
+        // shell, tui, watch and doctor
+        _ => return Err(CliError::Validation("interactive and long-running commands can't be started from here".to_owned()))
+    })
+}
+
+// the same checks back `run --dry-run`, so a script that validates fails on the server for server reasons only
+pub(crate) fn check_new_shelf(shelf_id: &str, slots: u32) -> Result<(), CliError> {
+    if shelf_id.trim().is_empty() {
+        return Err(CliError::Validation("the shelf id is empty".to_owned()));
+    }
+    if slots == 0 {
+        return Err(CliError::Validation("a shelf needs at least one slot".to_owned()));
+    }
+    Ok(())
+}
+
+pub(crate) async fn list_items(connection: &Connection, shelf_id: Option<String>) -> Result<Vec<ItemInfo>, CliError> {
+    match read_items(connection.rpc.clone(), shelf_id).await? {
+        GetItemsResponse::AllItems(items) | GetItemsResponse::ShelfItems(_, items) => Ok(items.items)
+    }
+}
+
+pub(crate) async fn fetch_item(connection: &Connection, item_id: u32) -> Result<ItemInfo, CliError> {
+    read_item(connection.rpc.clone(), item_id)
+        .await
+        .map_err(|e| match e {
+            RpcCallResult::NotFound(_) => CliError::NotFound(format!("item {}", item_id)),
+            _ => CliError::from(e)
+        })
+}
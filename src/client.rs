@@ -0,0 +1,257 @@
+//! The async [`SimsClient`] and its error type.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use tonic::{Code, Status};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+use crate::proto::{CreateShelfRequest, GetItemRequest, GetItemsRequest, GetShelvesRequest, LoginRequest, ShelfInfo};
+use crate::proto::sims_frontend_client::SimsFrontendClient;
+use crate::types::{Item, Shelf, Slot};
+
+/// How to secure the connection to the server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsOptions {
+    /// Connect over TLS. The other fields are ignored when this is off.
+    pub enabled: bool,
+    /// PEM file with the CA that signed the server certificate, the system roots are used when unset.
+    pub ca_certificate: Option<PathBuf>,
+    /// Name to verify the server certificate against, the host of the address when unset.
+    pub domain: Option<String>
+}
+
+/// Everything that can go wrong talking to the server.
+#[derive(Debug, Clone)]
+pub enum ClientError {
+    /// The address is invalid, the server could not be reached or the connection dropped.
+    Connection(String),
+    /// The method needs a session and [`SimsClient::login`] or [`SimsClient::use_token`] was not called.
+    NotLoggedIn,
+    /// The username, password or token was rejected.
+    Unauthenticated(String),
+    /// The requested shelf or item does not exist.
+    NotFound(String),
+    /// The thing being created already exists, e.g. a taken username.
+    AlreadyExists(String),
+    /// The server refused the request as invalid.
+    Rejected(String),
+    /// Any other status the server answered with.
+    Server(Status)
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Connection(reason) => write!(f, "connection failed: {}", reason),
+            ClientError::NotLoggedIn => write!(f, "not logged in"),
+            ClientError::Unauthenticated(reason) => write!(f, "not authenticated: {}", reason),
+            ClientError::NotFound(what) => write!(f, "not found: {}", what),
+            ClientError::AlreadyExists(what) => write!(f, "already exists: {}", what),
+            ClientError::Rejected(reason) => write!(f, "rejected: {}", reason),
+            ClientError::Server(status) => write!(f, "server error: {}", status)
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<Status> for ClientError {
+    fn from(status: Status) -> Self {
+        match status.code() {
+            Code::Unavailable => ClientError::Connection(status.message().to_owned()),
+            Code::Unauthenticated | Code::PermissionDenied => ClientError::Unauthenticated(status.message().to_owned()),
+            Code::NotFound => ClientError::NotFound(status.message().to_owned()),
+            Code::AlreadyExists => ClientError::AlreadyExists(status.message().to_owned()),
+            Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => ClientError::Rejected(status.message().to_owned()),
+            _ => ClientError::Server(status)
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Session {
+    username: String,
+    token: String
+}
+
+/// A connection to a SIMS server.
+///
+/// Connecting does not log in: call [`login`](SimsClient::login), [`sign_up`](SimsClient::sign_up)
+/// or [`use_token`](SimsClient::use_token) before anything that reads or changes the inventory.
+/// Items can only be read: the protocol has no call to create or change one.
+/// Methods take `&mut self` because the underlying tonic client does; clone the client to share
+/// it between tasks, clones reuse the same connection.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), cs4471_sims_cli_client::ClientError> {
+/// use cs4471_sims_cli_client::{SimsClient, TlsOptions};
+///
+/// let mut client = SimsClient::connect("http://localhost:50051", TlsOptions::default()).await?;
+/// client.login("alice", "correct horse").await?;
+/// for shelf in client.shelves().await? {
+///     println!("{} has {} slots", shelf.id, shelf.slots);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SimsClient {
+    rpc: SimsFrontendClient<Channel>,
+    session: Option<Session>
+}
+
+impl std::fmt::Debug for SimsClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // the token is a credential and stays out of logs
+        f.debug_struct("SimsClient").field("username", &self.username()).finish()
+    }
+}
+
+impl SimsClient {
+    /// Opens a connection to the server at `address`, e.g. `http://localhost:50051`.
+    pub async fn connect(address: impl Into<String>, tls: TlsOptions) -> Result<SimsClient, ClientError> {
+        let mut endpoint = Channel::from_shared(address.into()).map_err(|e| ClientError::Connection(e.to_string()))?;
+
+        if tls.enabled {
+            let mut tls_config = ClientTlsConfig::new();
+            if let Some(path) = tls.ca_certificate {
+                let pem = async_std::fs::read(&path).await
+                    .map_err(|e| ClientError::Connection(format!("could not read CA certificate {:?}: {}", path, e)))?;
+                tls_config = tls_config.ca_certificate(Certificate::from_pem(pem));
+            }
+            if let Some(domain) = tls.domain {
+                tls_config = tls_config.domain_name(domain);
+            }
+            endpoint = endpoint.tls_config(tls_config).map_err(|e| ClientError::Connection(e.to_string()))?;
+        }
+
+        let channel = endpoint.connect().await.map_err(|e| ClientError::Connection(e.to_string()))?;
+        Ok(SimsClient { rpc: SimsFrontendClient::new(channel), session: None })
+    }
+
+    /// The generated tonic client on the same connection, for calls this API does not cover.
+    pub fn rpc(&self) -> SimsFrontendClient<Channel> {
+        self.rpc.clone()
+    }
+
+    /// The user the client is logged in as, if any.
+    pub fn username(&self) -> Option<&str> {
+        self.session.as_ref().map(|s| s.username.as_str())
+    }
+
+    /// The session token of the current login, if any. It can be handed to
+    /// [`use_token`](SimsClient::use_token) later to skip the password.
+    pub fn token(&self) -> Option<&str> {
+        self.session.as_ref().map(|s| s.token.as_str())
+    }
+
+    /// Checks `username` and `password` and keeps the session token for later calls.
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<String, ClientError> {
+        let token = self.rpc
+            .cred_auth(LoginRequest { username: username.to_owned(), password: password.to_owned() })
+            .await?
+            .into_inner()
+            .token;
+        self.use_token(username, &token);
+        Ok(token)
+    }
+
+    /// Registers a new account and logs in with it.
+    ///
+    /// A taken username is [`ClientError::AlreadyExists`], a password or name the server
+    /// refuses is [`ClientError::Rejected`].
+    pub async fn sign_up(&mut self, username: &str, password: &str) -> Result<String, ClientError> {
+        self.rpc
+            .sign_up(LoginRequest { username: username.to_owned(), password: password.to_owned() })
+            .await
+            .map_err(|e| match e.code() {
+                Code::PermissionDenied => ClientError::Rejected(e.message().to_owned()),
+                _ => ClientError::from(e)
+            })?;
+        self.login(username, password).await
+    }
+
+    /// Uses a token from an earlier login without contacting the server.
+    /// Call [`check_session`](SimsClient::check_session) to find out whether it is still accepted.
+    pub fn use_token(&mut self, username: &str, token: &str) {
+        self.session = Some(Session { username: username.to_owned(), token: token.to_owned() });
+    }
+
+    /// Forgets the session token. The server has no logout call, so the token itself stays valid until it expires.
+    pub fn logout(&mut self) {
+        self.session = None;
+    }
+
+    /// Makes a cheap authenticated call to find out whether the session is still accepted.
+    pub async fn check_session(&mut self) -> Result<(), ClientError> {
+        self.shelves().await.map(|_| ())
+    }
+
+    /// Every shelf on the server.
+    pub async fn shelves(&mut self) -> Result<Vec<Shelf>, ClientError> {
+        self.get_shelves(None).await
+    }
+
+    /// A single shelf by id.
+    pub async fn shelf(&mut self, shelf_id: &str) -> Result<Shelf, ClientError> {
+        self.get_shelves(Some(shelf_id.to_owned())).await?
+            .into_iter()
+            .find(|s| s.id == shelf_id)
+            .ok_or_else(|| ClientError::NotFound(format!("shelf {}", shelf_id)))
+    }
+
+    async fn get_shelves(&mut self, shelf_id: Option<String>) -> Result<Vec<Shelf>, ClientError> {
+        let request = {
+            let session = self.session()?;
+            GetShelvesRequest { shelf_id, username: session.username.clone(), token: session.token.clone() }
+        };
+        Ok(self.rpc.get_shelves(request).await?.into_inner().shelves.into_iter().map(Shelf::from).collect())
+    }
+
+    /// Items on one shelf, or on every shelf when `shelf_id` is `None`.
+    pub async fn items(&mut self, shelf_id: Option<&str>) -> Result<Vec<Item>, ClientError> {
+        let request = {
+            let session = self.session()?;
+            GetItemsRequest { shelf_id: shelf_id.map(|s| s.to_owned()), username: session.username.clone(), token: session.token.clone() }
+        };
+        Ok(self.rpc.get_items(request).await?.into_inner().items.into_iter().map(Item::from).collect())
+    }
+
+    /// A single item by id.
+    pub async fn item(&mut self, item_id: u32) -> Result<Item, ClientError> {
+        let request = {
+            let session = self.session()?;
+            GetItemRequest { username: session.username.clone(), token: session.token.clone(), item_id }
+        };
+        self.rpc.get_singular_item(request).await
+            .map(|r| Item::from(r.into_inner()))
+            .map_err(|e| match e.code() {
+                Code::NotFound => ClientError::NotFound(format!("item {}", item_id)),
+                _ => ClientError::from(e)
+            })
+    }
+
+    /// The slots of a shelf, see [`Shelf::layout_slots`] for how they are derived.
+    pub async fn slots(&mut self, shelf_id: &str, capacity: u32) -> Result<Vec<Slot>, ClientError> {
+        let shelf = self.shelf(shelf_id).await?;
+        let items = self.items(Some(shelf_id)).await?;
+        Ok(shelf.layout_slots(&items, capacity))
+    }
+
+    /// Creates an empty shelf with `slots` slots.
+    pub async fn create_shelf(&mut self, shelf_id: &str, slots: u32) -> Result<(), ClientError> {
+        let request = {
+            let session = self.session()?;
+            CreateShelfRequest {
+                username: session.username.clone(),
+                token: session.token.clone(),
+                shelfinfo: Some(ShelfInfo { shelf_id: shelf_id.to_owned(), shelf_count: slots })
+            }
+        };
+        self.rpc.create_shelf(request).await?;
+        Ok(())
+    }
+
+    fn session(&self) -> Result<&Session, ClientError> {
+        self.session.as_ref().ok_or(ClientError::NotLoggedIn)
+    }
+}
@@ -0,0 +1,224 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use argon2::Argon2;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use crate::profiles::TlsSettings;
+use crate::session::state_dir;
+
+const CREDENTIALS_VERSION: u32 = 1;
+const CREDENTIALS_FILE: &str = "credentials.json";
+const PASSPHRASE_VARIABLE: &str = "SIMS_PASSPHRASE";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+// the remembered login, only ever written to disk encrypted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StoredCredential {
+    pub(crate) server: String,
+    #[serde(default)]
+    pub(crate) tls: TlsSettings,
+    pub(crate) username: String,
+    pub(crate) token: String
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>
+}
+
+fn credentials_path() -> Option<PathBuf> {
+    state_dir().map(|d| d.join(CREDENTIALS_FILE))
+}
+
+// only a secret the user chose is worth encrypting with, anything read off the machine is there for whoever reads the file
+fn local_passphrase() -> Option<String> {
+    env::var(PASSPHRASE_VARIABLE).ok().filter(|p| !p.is_empty())
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn can_remember() -> bool {
+    local_passphrase().is_some()
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> io::Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    Ok(key)
+}
+
+impl StoredCredential {
+    pub(crate) fn load() -> Option<StoredCredential> {
+        let path = credentials_path()?;
+        let contents = fs::read_to_string(&path).ok()?;
+
+        let file = match serde_json::from_str::<EncryptedFile>(&contents) {
+            Ok(f) if f.version == CREDENTIALS_VERSION => f,
+            Ok(f) => {
+                warn!("Ignoring saved login with unsupported version {}", f.version);
+                return None;
+            }
+            Err(e) => {
+                warn!("Saved login is corrupt: {}", e);
+                StoredCredential::forget();
+                return None;
+            }
+        };
+
+        // without the passphrase in this environment the file is left for one that has it
+        let passphrase = local_passphrase()?;
+        // a changed passphrase or tampered file both end up here, neither is recoverable
+        let credential = StoredCredential::decrypt(&file, &passphrase);
+        if credential.is_none() {
+            warn!("Saved login could not be decrypted, it will be discarded");
+            StoredCredential::forget();
+        }
+        credential
+    }
+
+    pub(crate) fn save(&self) -> io::Result<()> {
+        let path = credentials_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let passphrase = local_passphrase()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} is not set", PASSPHRASE_VARIABLE)))?;
+        let contents = serde_json::to_vec(&self.encrypt(&passphrase)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut file = private_file(&path)?;
+        file.write_all(&contents)
+    }
+
+    fn encrypt(&self, passphrase: &str) -> io::Result<EncryptedFile> {
+        let mut salt = vec![0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(passphrase, &salt)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext = serde_json::to_vec(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let ciphertext = ChaCha20Poly1305::new(&key)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "failed to encrypt login"))?;
+
+        Ok(EncryptedFile {
+            version: CREDENTIALS_VERSION,
+            salt,
+            nonce: nonce.to_vec(),
+            ciphertext
+        })
+    }
+
+    fn decrypt(file: &EncryptedFile, passphrase: &str) -> Option<StoredCredential> {
+        if file.nonce.len() != NONCE_LENGTH {
+            return None;
+        }
+        let key = derive_key(passphrase, &file.salt).ok()?;
+        let plaintext = ChaCha20Poly1305::new(&key).decrypt(Nonce::from_slice(&file.nonce), file.ciphertext.as_slice()).ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+
+    // only JWTs carry their expiry, any other kind of token gives no way to tell
+    #[cfg(feature = "cli")]
+    pub(crate) fn expires_at(&self) -> Option<SystemTime> {
+        let parts = self.token.split('.').collect::<Vec<&str>>();
+        if parts.len() != 3 {
+            return None;
+        }
+        let payload = base64::decode_config(parts[1], base64::URL_SAFE_NO_PAD).ok()?;
+        let claims = serde_json::from_slice::<serde_json::Value>(&payload).ok()?;
+        Some(UNIX_EPOCH + Duration::from_secs(claims.get("exp")?.as_u64()?))
+    }
+
+    pub(crate) fn forget() {
+        if let Some(path) = credentials_path() {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    warn!("Failed to remove saved login: {}", e);
+                }
+            } else {
+                debug!("Removed saved login at {:?}", path);
+            }
+        }
+    }
+}
+
+// owner read/write only, permissions are tightened on existing files too in case they were loosened
+#[cfg(unix)]
+fn private_file(path: &PathBuf) -> io::Result<fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn private_file(path: &PathBuf) -> io::Result<fs::File> {
+    fs::OpenOptions::new().write(true).create(true).truncate(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential() -> StoredCredential {
+        StoredCredential {
+            server: "https://sims.example:50051".to_owned(),
+            tls: TlsSettings::default(),
+            username: "alice".to_owned(),
+            token: "secret-token".to_owned()
+        }
+    }
+
+    // the file as it would be read back from disk
+    fn written(credential: &StoredCredential, passphrase: &str) -> EncryptedFile {
+        let contents = serde_json::to_vec(&credential.encrypt(passphrase).unwrap()).unwrap();
+        serde_json::from_slice(&contents).unwrap()
+    }
+
+    #[test]
+    fn decrypts_what_it_encrypted() {
+        let file = written(&credential(), "correct horse");
+        assert_eq!(StoredCredential::decrypt(&file, "correct horse"), Some(credential()));
+    }
+
+    #[test]
+    fn the_token_is_not_stored_in_the_clear() {
+        let contents = serde_json::to_string(&credential().encrypt("correct horse").unwrap()).unwrap();
+        assert!(!contents.contains("secret-token"));
+        assert!(!contents.contains("alice"));
+    }
+
+    #[test]
+    fn a_wrong_passphrase_decrypts_nothing() {
+        let file = written(&credential(), "correct horse");
+        assert_eq!(StoredCredential::decrypt(&file, "battery staple"), None);
+    }
+
+    #[test]
+    fn a_tampered_file_decrypts_nothing() {
+        let mut file = written(&credential(), "correct horse");
+        file.ciphertext[0] ^= 1;
+        assert_eq!(StoredCredential::decrypt(&file, "correct horse"), None);
+
+        let mut file = written(&credential(), "correct horse");
+        file.salt[0] ^= 1;
+        assert_eq!(StoredCredential::decrypt(&file, "correct horse"), None);
+
+        let mut file = written(&credential(), "correct horse");
+        file.nonce.pop();
+        assert_eq!(StoredCredential::decrypt(&file, "correct horse"), None);
+    }
+}
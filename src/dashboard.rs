@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use iced::{Color, Point, Rectangle, Size, Theme};
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::{Cursor, Frame, Geometry, Program, Text as CanvasText};
+use crate::frontend::{DEFAULT_SLOT_CAPACITY, shelf_slots};
+use crate::frontend::sims_ims_frontend::{ItemInfo, ShelfInfo, SlotInfo};
+use crate::i18n::format_number;
+use crate::ui_messages::Message;
+
+const HISTOGRAM_BUCKETS: [(u32, Option<u32>); 6] = [(0, Some(0)), (1, Some(9)), (10, Some(24)), (25, Some(49)), (50, Some(99)), (100, None)];
+const LABEL_SIZE: f32 = 14.0;
+const BAR_GAP: f32 = 6.0;
+
+#[derive(Debug, Default)]
+pub(crate) struct InventoryStats {
+    pub(crate) total_skus: usize,
+    pub(crate) total_units: u64,
+    pub(crate) total_value: u64,
+    pub(crate) shelf_utilisation: Vec<(String, f32)>,
+    pub(crate) stock_histogram: Vec<(String, u32)>
+}
+
+impl InventoryStats {
+    pub(crate) fn compute(shelves: &[ShelfInfo], all_items: &HashMap<String, Vec<ItemInfo>>) -> Self {
+        let items = all_items.values().flatten();
+
+        // an estimate, the server reports no slots so they come from Shelf::layout_slots
+        let shelf_utilisation = shelves.iter().map(|shelf| {
+            let slots = shelf_slots(shelf, all_items.get(&shelf.shelf_id).map(|i| i.as_slice()).unwrap_or(&[]), DEFAULT_SLOT_CAPACITY);
+            let capacity = slots.iter().map(|s| s.capacity as u64).sum::<u64>();
+            let used = slots.iter().map(|s| s.item_count.min(s.capacity) as u64).sum::<u64>();
+            let utilisation = if capacity == 0 { 0.0 } else { used as f32 / capacity as f32 };
+            (shelf.shelf_id.clone(), utilisation)
+        }).collect();
+
+        let stock_histogram = HISTOGRAM_BUCKETS.iter().map(|(low, high)| {
+            let label = match high {
+                Some(h) if h == low => format!("{}", low),
+                Some(h) => format!("{}-{}", low, h),
+                None => format!("{}+", low)
+            };
+            let count = all_items.values().flatten()
+                .filter(|i| i.stock >= *low && high.map_or(true, |h| i.stock <= h))
+                .count() as u32;
+            (label, count)
+        }).collect();
+
+        InventoryStats {
+            total_skus: items.clone().count(),
+            total_units: items.clone().map(|i| i.stock as u64).sum(),
+            total_value: items.map(|i| i.price as u64 * i.stock as u64).sum(),
+            shelf_utilisation,
+            stock_histogram
+        }
+    }
+}
+
+// horizontal bars, one per shelf, filled to the fraction of slot capacity in use
+pub(crate) struct UtilisationChart {
+    pub(crate) bars: Vec<(String, f32)>
+}
+
+impl Program<Message> for UtilisationChart {
+    type State = ();
+
+    fn draw(&self, _state: &Self::State, theme: &Theme, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let palette = theme.palette();
+
+        if self.bars.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+
+        let label_width = bounds.width * 0.25;
+        let bar_width = bounds.width - label_width;
+        let bar_height = (bounds.height / self.bars.len() as f32 - BAR_GAP).max(1.0);
+
+        for (i, (shelf_id, utilisation)) in self.bars.iter().enumerate() {
+            let y = i as f32 * (bar_height + BAR_GAP);
+            frame.fill_text(CanvasText {
+                content: shelf_id.clone(),
+                position: Point::new(0.0, y + bar_height / 2.0),
+                color: palette.text,
+                size: LABEL_SIZE,
+                vertical_alignment: Vertical::Center,
+                ..CanvasText::default()
+            });
+            frame.fill_rectangle(
+                Point::new(label_width, y),
+                Size::new(bar_width, bar_height),
+                Color { a: 0.2, ..palette.text }
+            );
+            frame.fill_rectangle(
+                Point::new(label_width, y),
+                Size::new(bar_width * utilisation.clamp(0.0, 1.0), bar_height),
+                palette.primary
+            );
+            frame.fill_text(CanvasText {
+                content: format!("{:.0}%", utilisation * 100.0),
+                position: Point::new(bounds.width, y + bar_height / 2.0),
+                color: palette.text,
+                size: LABEL_SIZE,
+                horizontal_alignment: Horizontal::Right,
+                vertical_alignment: Vertical::Center,
+                ..CanvasText::default()
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+// vertical bars counting how many items fall into each stock range
+pub(crate) struct StockHistogram {
+    pub(crate) buckets: Vec<(String, u32)>
+}
+
+impl Program<Message> for StockHistogram {
+    type State = ();
+
+    fn draw(&self, _state: &Self::State, theme: &Theme, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let palette = theme.palette();
+
+        if self.buckets.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+
+        let tallest = self.buckets.iter().map(|(_, c)| *c).max().unwrap_or(0).max(1);
+        let chart_height = bounds.height - LABEL_SIZE * 2.0;
+        let bar_width = (bounds.width / self.buckets.len() as f32 - BAR_GAP).max(1.0);
+
+        for (i, (label, count)) in self.buckets.iter().enumerate() {
+            let x = i as f32 * (bar_width + BAR_GAP);
+            let height = chart_height * (*count as f32 / tallest as f32);
+            frame.fill_rectangle(
+                Point::new(x, LABEL_SIZE + chart_height - height),
+                Size::new(bar_width, height),
+                palette.primary
+            );
+            frame.fill_text(CanvasText {
+                content: format_number(*count as u64),
+                position: Point::new(x + bar_width / 2.0, LABEL_SIZE + chart_height - height),
+                color: palette.text,
+                size: LABEL_SIZE,
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Bottom,
+                ..CanvasText::default()
+            });
+            frame.fill_text(CanvasText {
+                content: label.clone(),
+                position: Point::new(x + bar_width / 2.0, bounds.height),
+                color: palette.text,
+                size: LABEL_SIZE,
+                horizontal_alignment: Horizontal::Center,
+                vertical_alignment: Vertical::Bottom,
+                ..CanvasText::default()
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+// one cell per slot, shaded by how full the slot is and flagged when it holds more than its capacity
+pub(crate) struct SlotHeatmap {
+    pub(crate) slots: Vec<SlotInfo>
+}
+
+impl Program<Message> for SlotHeatmap {
+    type State = ();
+
+    fn draw(&self, _state: &Self::State, theme: &Theme, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let palette = theme.palette();
+
+        if self.slots.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+
+        let cell_width = bounds.width / self.slots.len() as f32;
+
+        for (i, slot) in self.slots.iter().enumerate() {
+            let color = if slot.item_count > slot.capacity {
+                palette.danger
+            } else if slot.capacity == 0 || slot.item_count == 0 {
+                Color { a: 0.15, ..palette.text }
+            } else {
+                Color { a: 0.25 + 0.75 * (slot.item_count as f32 / slot.capacity as f32), ..palette.primary }
+            };
+            frame.fill_rectangle(
+                Point::new(i as f32 * cell_width, 0.0),
+                Size::new((cell_width - 1.0).max(1.0), bounds.height),
+                color
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shelf(shelf_id: &str, shelf_count: u32) -> ShelfInfo {
+        ShelfInfo { shelf_id: shelf_id.to_owned(), shelf_count }
+    }
+
+    fn item(object_id: u32, shelf_id: &str, stock: u32, price: u32) -> ItemInfo {
+        ItemInfo { object_id, shelf_id: shelf_id.to_owned(), description: format!("item {}", object_id), stock, price }
+    }
+
+    fn inventory() -> (Vec<ShelfInfo>, HashMap<String, Vec<ItemInfo>>) {
+        let shelves = vec![shelf("A1", 2), shelf("B2", 1), shelf("C3", 0)];
+        let mut all_items = HashMap::new();
+        all_items.insert("A1".to_owned(), vec![item(1, "A1", 10, 250), item(2, "A1", 0, 100)]);
+        all_items.insert("B2".to_owned(), vec![item(3, "B2", 45, 1000)]);
+        (shelves, all_items)
+    }
+
+    #[test]
+    fn totals_count_every_item() {
+        let (shelves, all_items) = inventory();
+        let stats = InventoryStats::compute(&shelves, &all_items);
+        assert_eq!(stats.total_skus, 3);
+        assert_eq!(stats.total_units, 55);
+        assert_eq!(stats.total_value, 10 * 250 + 45 * 1000);
+    }
+
+    #[test]
+    fn utilisation_caps_overfull_slots_and_handles_empty_shelves() {
+        let (shelves, all_items) = inventory();
+        let stats = InventoryStats::compute(&shelves, &all_items);
+        let capacity = DEFAULT_SLOT_CAPACITY as f32;
+        assert_eq!(stats.shelf_utilisation, vec![
+            ("A1".to_owned(), 10.0 / (2.0 * capacity)),
+            ("B2".to_owned(), 1.0),
+            ("C3".to_owned(), 0.0)
+        ]);
+    }
+
+    #[test]
+    fn histogram_buckets_are_inclusive() {
+        let (shelves, all_items) = inventory();
+        let stats = InventoryStats::compute(&shelves, &all_items);
+        let counts = stats.stock_histogram.iter().map(|(label, count)| (label.as_str(), *count)).collect::<Vec<(&str, u32)>>();
+        assert_eq!(counts, vec![("0", 1), ("1-9", 0), ("10-24", 1), ("25-49", 1), ("50-99", 0), ("100+", 0)]);
+    }
+
+    #[test]
+    fn no_inventory_gives_empty_stats() {
+        let stats = InventoryStats::compute(&[], &HashMap::new());
+        assert_eq!((stats.total_skus, stats.total_units, stats.total_value), (0, 0, 0));
+        assert!(stats.shelf_utilisation.is_empty());
+        assert!(stats.stock_histogram.iter().all(|(_, count)| *count == 0));
+    }
+}
@@ -0,0 +1,255 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::timeout;
+use tonic::{Code, Response, Status};
+use tonic::transport::Uri;
+use cs4471_sims_cli_client::proto::{ClientAction, CreateShelfRequest, GetItemRequest, GetItemsRequest, GetShelvesRequest, LoginRequest};
+use cs4471_sims_cli_client::{ClientError, SimsClient};
+use crate::cli::{Cli, CliError, Credential, resolve_credential, resolve_profile};
+use crate::frontend::RpcCallResult;
+use crate::money::MoneyFormat;
+use crate::output::{CheckStatus, DoctorCheckRecord, OutputFormat, print_records};
+
+const STEP_TIMEOUT: Duration = Duration::from_secs(5);
+const LATENCY_SAMPLES: u32 = 5;
+// no server knows this action, so ClientCmd can be probed without changing anything
+const PROBE_ACTION: &str = "doctor_probe";
+
+#[derive(Default)]
+struct Report {
+    checks: Vec<DoctorCheckRecord>,
+    first_failure: Option<CliError>
+}
+
+impl Report {
+    fn add(&mut self, check: &str, status: CheckStatus, started: Option<Instant>, detail: String) {
+        let millis = started.map(|s| s.elapsed().as_millis() as u64);
+        self.checks.push(DoctorCheckRecord { check: check.to_owned(), status, millis, detail });
+    }
+
+    fn pass(&mut self, check: &str, started: Option<Instant>, detail: String) {
+        self.add(check, CheckStatus::Pass, started, detail);
+    }
+
+    fn fail(&mut self, check: &str, started: Option<Instant>, detail: String, error: CliError) {
+        self.add(check, CheckStatus::Fail, started, detail);
+        self.first_failure.get_or_insert(error);
+    }
+
+    fn skip(&mut self, check: &str, detail: &str) {
+        self.add(check, CheckStatus::Skip, None, detail.to_owned());
+    }
+
+    // the report is printed whatever happened, the exit code is that of the first failed check
+    fn finish(self, output: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+        print_records(&self.checks, output, money_format)?;
+        match self.first_failure {
+            Some(e) => Err(e),
+            None => Ok(())
+        }
+    }
+}
+
+fn client_error(error: ClientError) -> CliError {
+    CliError::from(RpcCallResult::from(error))
+}
+
+// only a missing method or no answer at all fails, a probe with empty fields being refused shows the method is there
+async fn probe<T>(report: &mut Report, method: &str, call: impl Future<Output = Result<Response<T>, Status>>) {
+    let check = format!("rpc {}", method);
+    let started = Instant::now();
+    match timeout(STEP_TIMEOUT, call).await {
+        Err(_) => report.fail(&check, Some(started), "timed out".to_owned(), CliError::Connection(format!("{} timed out", method))),
+        Ok(Ok(_)) => report.pass(&check, Some(started), "answered".to_owned()),
+        Ok(Err(status)) => match status.code() {
+            Code::Unimplemented => report.fail(&check, Some(started), "not implemented by the server".to_owned(), CliError::Server(format!("{} is not implemented", method))),
+            Code::Unavailable | Code::DeadlineExceeded | Code::Cancelled => {
+                report.fail(&check, Some(started), format!("no answer: {}", status.message()), CliError::Connection(status.message().to_owned()))
+            }
+            code => report.pass(&check, Some(started), format!("answered {:?}: {}", code, status.message()))
+        }
+    }
+}
+
+async fn resolve(report: &mut Report, host: &str, port: u16) -> Option<Vec<SocketAddr>> {
+    let started = Instant::now();
+    let detail = match timeout(STEP_TIMEOUT, lookup_host((host, port))).await {
+        Ok(Ok(addresses)) => {
+            let addresses = addresses.collect::<Vec<SocketAddr>>();
+            if !addresses.is_empty() {
+                let list = addresses.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(", ");
+                report.pass("dns", Some(started), format!("{} resolves to {}", host, list));
+                return Some(addresses);
+            }
+            format!("{} has no addresses", host)
+        }
+        Ok(Err(e)) => format!("{} could not be resolved: {}", host, e),
+        Err(_) => format!("looking up {} timed out", host)
+    };
+    report.fail("dns", Some(started), detail.clone(), CliError::Connection(detail));
+    None
+}
+
+// every resolved address is tried in turn, the way the connection itself would
+async fn connect_tcp(report: &mut Report, addresses: &[SocketAddr]) -> bool {
+    let started = Instant::now();
+    let mut errors = Vec::new();
+    for address in addresses {
+        match timeout(STEP_TIMEOUT, TcpStream::connect(address)).await {
+            Ok(Ok(_)) => {
+                report.pass("tcp", Some(started), format!("connected to {}", address));
+                return true;
+            }
+            Ok(Err(e)) => errors.push(format!("{}: {}", address, e)),
+            Err(_) => errors.push(format!("{}: timed out", address))
+        }
+    }
+    let detail = errors.join(", ");
+    report.fail("tcp", Some(started), detail.clone(), CliError::Connection(detail));
+    false
+}
+
+async fn check_login(report: &mut Report, client: &mut SimsClient, username: &str, credential: Option<Credential>) -> bool {
+    let credential = match credential {
+        Some(credential) => credential,
+        None => {
+            report.skip("login", "no password, token or cached login given");
+            return false;
+        }
+    };
+    if username.is_empty() {
+        report.fail("login", None, "no username given".to_owned(), CliError::Auth("no username given".to_owned()));
+        return false;
+    }
+
+    let started = Instant::now();
+    let (result, detail) = match credential {
+        Credential::Password(password) => (timeout(STEP_TIMEOUT, client.login(username, &password)).await.map(|r| r.map(|_| ())), "password"),
+        Credential::Token(token) => {
+            client.use_token(username, &token);
+            (timeout(STEP_TIMEOUT, client.check_session()).await, "token")
+        }
+    };
+    match result {
+        Ok(Ok(())) => {
+            report.pass("login", Some(started), format!("{} accepted for {}", detail, username));
+            true
+        }
+        Ok(Err(e)) => {
+            report.fail("login", Some(started), format!("{} rejected: {}", detail, e), client_error(e));
+            false
+        }
+        Err(_) => {
+            report.fail("login", Some(started), "timed out".to_owned(), CliError::Connection("login timed out".to_owned()));
+            false
+        }
+    }
+}
+
+// a logged in client measures a real read, otherwise the cheapest call that needs no account
+async fn check_latency(report: &mut Report, client: &mut SimsClient, logged_in: bool) {
+    let mut samples = Vec::new();
+    for _ in 0..LATENCY_SAMPLES {
+        let started = Instant::now();
+        let answered = if logged_in {
+            timeout(STEP_TIMEOUT, client.check_session()).await.map(|r| r.is_ok()).unwrap_or(false)
+        } else {
+            match timeout(STEP_TIMEOUT, client.rpc().cred_auth(LoginRequest::default())).await {
+                Ok(Ok(_)) => true,
+                Ok(Err(status)) => !matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded | Code::Cancelled),
+                Err(_) => false
+            }
+        };
+        if answered {
+            samples.push(started.elapsed());
+        }
+    }
+
+    if samples.is_empty() {
+        report.fail("latency", None, "no round trip completed".to_owned(), CliError::Connection("no round trip completed".to_owned()));
+        return;
+    }
+    let millis = |d: &Duration| d.as_secs_f64() * 1000.0;
+    let min = samples.iter().map(millis).fold(f64::MAX, f64::min);
+    let max = samples.iter().map(millis).fold(0.0, f64::max);
+    let average = samples.iter().map(millis).sum::<f64>() / samples.len() as f64;
+    let method = if logged_in { "GetShelves" } else { "CredAuth" };
+    report.checks.push(DoctorCheckRecord {
+        check: "latency".to_owned(),
+        status: CheckStatus::Pass,
+        millis: Some(average.round() as u64),
+        detail: format!("{} of {} {} calls answered, min {:.1} ms, avg {:.1} ms, max {:.1} ms", samples.len(), LATENCY_SAMPLES, method, min, average, max)
+    });
+}
+
+// each step needs the one before, so the first network failure ends the run and the rest of the report stays empty
+pub(crate) async fn run_doctor(cli: &Cli, probe_writes: bool, output: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+    let mut profile = resolve_profile(cli)?;
+    let credential = resolve_credential(cli, &mut profile)?;
+    let mut report = Report::default();
+    report.pass("client", None, format!("version {}", env!("CARGO_PKG_VERSION")));
+
+    let uri = match profile.server.parse::<Uri>() {
+        Ok(uri) if uri.host().is_some() => uri,
+        _ => {
+            let detail = format!("{} is not a valid server address", profile.server);
+            report.fail("endpoint", None, detail.clone(), CliError::Validation(detail));
+            return report.finish(output, money_format);
+        }
+    };
+    report.pass("endpoint", None, format!("{} with TLS {}", profile.server, if profile.tls.enabled { "on" } else { "off" }));
+
+    let host = uri.host().unwrap_or_default().trim_start_matches('[').trim_end_matches(']').to_owned();
+    let port = uri.port_u16().unwrap_or(if uri.scheme_str() == Some("https") { 443 } else { 80 });
+    let addresses = match resolve(&mut report, &host, port).await {
+        Some(addresses) => addresses,
+        None => return report.finish(output, money_format)
+    };
+    if !connect_tcp(&mut report, &addresses).await {
+        return report.finish(output, money_format);
+    }
+
+    // tonic sets up TLS and HTTP/2 in one go, with TLS on a failure here is almost always the handshake
+    let connect_check = if profile.tls.enabled {
+        "tls"
+    } else {
+        report.skip("tls", "TLS is off for this server");
+        "channel"
+    };
+    let started = Instant::now();
+    let mut client = match timeout(STEP_TIMEOUT, SimsClient::connect(profile.server.clone(), profile.tls.clone().into())).await {
+        Ok(Ok(client)) => {
+            report.pass(connect_check, Some(started), if profile.tls.enabled { "handshake completed" } else { "HTTP/2 connection open" }.to_owned());
+            client
+        }
+        Ok(Err(e)) => {
+            report.fail(connect_check, Some(started), e.to_string(), client_error(e));
+            return report.finish(output, money_format);
+        }
+        Err(_) => {
+            report.fail(connect_check, Some(started), "timed out".to_owned(), CliError::Connection("connecting timed out".to_owned()));
+            return report.finish(output, money_format);
+        }
+    };
+
+    // only calls that can't change anything by default, a server that doesn't validate could create an account or shelf from the others
+    let mut rpc = client.rpc();
+    probe(&mut report, "CredAuth", rpc.cred_auth(LoginRequest::default())).await;
+    probe(&mut report, "ClientCmd", rpc.client_cmd(ClientAction { action: PROBE_ACTION.to_owned(), ..ClientAction::default() })).await;
+    probe(&mut report, "GetShelves", rpc.get_shelves(GetShelvesRequest::default())).await;
+    probe(&mut report, "GetItems", rpc.get_items(GetItemsRequest::default())).await;
+    probe(&mut report, "GetSingularItem", rpc.get_singular_item(GetItemRequest::default())).await;
+    if probe_writes {
+        probe(&mut report, "SignUp", rpc.sign_up(LoginRequest::default())).await;
+        probe(&mut report, "CreateShelf", rpc.create_shelf(CreateShelfRequest::default())).await;
+    } else {
+        report.skip("rpc SignUp", "writes are only probed with --probe-writes");
+        report.skip("rpc CreateShelf", "writes are only probed with --probe-writes");
+    }
+
+    let logged_in = check_login(&mut report, &mut client, &profile.username, credential).await;
+    check_latency(&mut report, &mut client, logged_in).await;
+    report.finish(output, money_format)
+}
@@ -1,43 +1,89 @@
-use crate::frontend::sims_ims_frontend::{ActionApproved, CreateShelfRequest, GetItemRequest, GetItemsRequest, GetShelvesRequest, Items, LoginRequest, ShelfInfo, Shelves, Token};
-use crate::frontend::LoginResult::{NotConnected, RegisterFailed, ServerError};
+use crate::frontend::sims_ims_frontend::{ItemInfo, Items, ShelfInfo, Shelves, SlotInfo, Token};
+use crate::frontend::LoginResult::{NotConnected, ServerError, SessionExpired};
+#[cfg(feature = "gui")]
+use crate::frontend::LoginResult::{RegistrationRejected, UsernameTaken};
 use async_std::sync::Arc;
-use iced::futures::lock::Mutex;
+use std::fmt::{Display, Formatter};
+use async_std::sync::Mutex;
+#[cfg(feature = "gui")]
 use iced::widget::{Button, Container, Row, Space, Svg, svg, Text};
-use iced::{Length, Subscription, subscription};
-use iced::futures::{StreamExt, TryStreamExt};
+#[cfg(feature = "gui")]
+use iced::{Length, theme};
+#[cfg(feature = "gui")]
 use iced::Length::{Fill, Shrink};
-use tonic::codegen::Body;
-use sims_ims_frontend::sims_frontend_client::SimsFrontendClient;
-use tonic::transport::Channel;
+use serde::{Deserialize, Serialize};
+use cs4471_sims_cli_client::{ClientError, Item, Shelf, SimsClient};
+#[cfg(any(feature = "gui", feature = "tui"))]
+use cs4471_sims_cli_client::Slot;
+use log::debug;
+#[cfg(feature = "gui")]
 use crate::assets::{CLOSE_ICON, get_icon};
 use crate::frontend::TabId::AllShelves;
-use crate::ui_messages;
+use crate::i18n::tr;
+use crate::profiles::TlsSettings;
+#[cfg(feature = "gui")]
+use crate::styles::Tab;
+#[cfg(feature = "gui")]
 use crate::ui_messages::Message;
-use crate::ui_messages::Message::{CloseShelf, TabSelected};
+#[cfg(feature = "gui")]
+use crate::ui_messages::Message::{CloseShelf, TabMoveStarted, TabMovedTo, TabSelected, TogglePinTab};
 
-pub mod sims_ims_frontend {
-    tonic::include_proto!("sims_ims_frontend");
-}
+pub(crate) use cs4471_sims_cli_client::proto as sims_ims_frontend;
+pub(crate) use cs4471_sims_cli_client::DEFAULT_SLOT_CAPACITY;
+
+// one client shared by every command, None until connected and again after logging out
+pub(crate) type SharedClient = Arc<Mutex<Option<SimsClient>>>;
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum TabId {
     AllShelves,
     AllItems,
+    Dashboard,
     ShelfView(String)
 }
 
+impl Display for TabId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TabId::AllShelves => write!(f, "{}", tr("tab-shelves")),
+            TabId::AllItems => write!(f, "{}", tr("tab-items")),
+            TabId::Dashboard => write!(f, "{}", tr("tab-dashboard")),
+            TabId::ShelfView(shelf_id) => write!(f, "{}", shelf_id)
+        }
+    }
+}
+
 impl Default for &TabId {
     fn default() -> Self {
         &AllShelves
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ShelfSortColumn {
+    Name,
+    Slots,
+    PercentFull
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct ShelfSort {
+    pub(crate) column: ShelfSortColumn,
+    pub(crate) descending: bool
+}
+
+impl Default for ShelfSort {
+    fn default() -> Self {
+        ShelfSort { column: ShelfSortColumn::Name, descending: false }
+    }
+}
+
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone)]
 pub(crate) enum EditTarget {
     EditShelf{shelf_id: String},
     EditItem{shelf_id: String, item_id: String},
     EditSlot{shelf_id: String, slot_id: String},
-    NewItem{shelf_id: String, item_name: String, item_count: String, error_message: Option<String>},
     NewShelf{shelf_name: String, slots: String, error_message: Option<String>}
 }
 
@@ -45,74 +91,147 @@ pub(crate) enum EditTarget {
 pub(crate) enum LoginResult {
     ServerError(tonic::Status),
     NotConnected,
-    RegisterFailed
+    #[cfg(feature = "gui")]
+    UsernameTaken,
+    #[cfg(feature = "gui")]
+    RegistrationRejected(String),
+    SessionExpired
 }
 
 #[derive(Debug, Clone)]
 pub enum RpcCallResult {
     NotConnected,
+    NotFound(String),
+    Denied(String),
     CallFailed(String)
 }
 
+impl From<ClientError> for RpcCallResult {
+    fn from(error: ClientError) -> Self {
+        match error {
+            ClientError::Connection(_) => RpcCallResult::NotConnected,
+            ClientError::NotFound(what) => RpcCallResult::NotFound(what),
+            ClientError::NotLoggedIn => RpcCallResult::Denied(error.to_string()),
+            ClientError::Unauthenticated(reason) => RpcCallResult::Denied(reason),
+            error => RpcCallResult::CallFailed(error.to_string())
+        }
+    }
+}
+
+impl From<ClientError> for LoginResult {
+    fn from(error: ClientError) -> Self {
+        match error {
+            ClientError::Connection(reason) => {
+                debug!("Could not connect: {}", reason);
+                NotConnected
+            }
+            ClientError::Server(status) => ServerError(status),
+            ClientError::NotLoggedIn => SessionExpired,
+            ClientError::Unauthenticated(reason) => ServerError(tonic::Status::unauthenticated(reason)),
+            ClientError::NotFound(reason) => ServerError(tonic::Status::not_found(reason)),
+            ClientError::AlreadyExists(reason) => ServerError(tonic::Status::already_exists(reason)),
+            ClientError::Rejected(reason) => ServerError(tonic::Status::invalid_argument(reason))
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum GetItemsResponse {
     AllItems(Items),
     ShelfItems(String, Items)
 }
 
+pub(crate) async fn connect(address: String, tls: TlsSettings) -> Result<SimsClient, LoginResult> {
+    SimsClient::connect(address, tls.into()).await.map_err(LoginResult::from)
+}
+
 pub(crate) async fn login(
-    rpc: Arc<Mutex<Option<SimsFrontendClient<Channel>>>>,
+    rpc: SharedClient,
     address: String,
+    tls: TlsSettings,
     username: String,
     password: String,
 ) -> Result<Token, LoginResult> {
     let mut rpc_present = match rpc.lock().await.take() {
-        None => SimsFrontendClient::connect(address)
-            .await
-            .map_err(|_| NotConnected)?,
+        None => connect(address, tls).await?,
         Some(client_rpc) => client_rpc,
     };
 
     let response = rpc_present
-        .cred_auth(LoginRequest { username, password })
+        .login(&username, &password)
         .await
-        .map(|x| x.into_inner())
-        .map_err(|e| ServerError(e));
-    let _ = rpc.lock().await.insert(rpc_present);
+        .map(|token| Token { token })
+        .map_err(LoginResult::from);
+    // a failed attempt keeps nothing, the next one may be for another server or profile
+    if response.is_ok() {
+        let _ = rpc.lock().await.insert(rpc_present);
+    }
 
     response
 }
 
+#[cfg(feature = "gui")]
 pub(crate) async fn register_and_login(
-    rpc: Arc<Mutex<Option<SimsFrontendClient<Channel>>>>,
+    rpc: SharedClient,
     address: String,
+    tls: TlsSettings,
     username: String,
     password: String,
 ) -> Result<Token, LoginResult> {
     let mut rpc_present = match rpc.lock().await.take() {
-        None => SimsFrontendClient::connect(address)
-            .await
-            .map_err(|_| NotConnected)?,
+        None => connect(address, tls).await?,
         Some(client_rpc) => client_rpc,
     };
 
-    let _register_response = rpc_present.sign_up(LoginRequest{
-        username: username.clone(),
-        password: password.clone(),
-    }).await.map_err(|_|RegisterFailed)?;
-
     let response = rpc_present
-        .cred_auth(LoginRequest { username, password })
+        .sign_up(&username, &password)
         .await
-        .map(|x| x.into_inner())
-        .map_err(|e| ServerError(e));
-    let _ = rpc.lock().await.insert(rpc_present);
+        .map(|token| Token { token })
+        .map_err(|e| match e {
+            ClientError::AlreadyExists(_) => UsernameTaken,
+            ClientError::Rejected(reason) => RegistrationRejected(reason),
+            e => LoginResult::from(e)
+        });
+    if response.is_ok() {
+        let _ = rpc.lock().await.insert(rpc_present);
+    }
 
     response
 }
 
 
-pub(crate) fn create_tab<'a>(tab_id: TabId, text_content: String, closeable: bool, icon: Option<char>) -> Button<'a, Message> {
+// reconnects with a remembered token, any authenticated call works to prove the token is still accepted
+#[cfg(feature = "gui")]
+pub(crate) async fn resume_session(
+    rpc: SharedClient,
+    address: String,
+    tls: TlsSettings,
+    username: String,
+    token: String,
+) -> Result<Token, LoginResult> {
+    let mut rpc_present = match rpc.lock().await.take() {
+        None => connect(address, tls).await?,
+        Some(client_rpc) => client_rpc,
+    };
+
+    rpc_present.use_token(&username, &token);
+    let response = rpc_present
+        .check_session()
+        .await
+        .map(|_| Token { token })
+        .map_err(|e| match e {
+            ClientError::Connection(_) => NotConnected,
+            _ => SessionExpired,
+        });
+    if response.is_ok() {
+        let _ = rpc.lock().await.insert(rpc_present);
+    }
+
+    response
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn create_tab<'a>(tab_id: TabId, text_content: String, closeable: bool, icon: Option<char>, pinned: bool, active: bool, moving: bool) -> Button<'a, Message> {
     let mut button_display = Row::new();
 
     if let Some(c) = icon {
@@ -124,7 +243,19 @@ pub(crate) fn create_tab<'a>(tab_id: TabId, text_content: String, closeable: boo
 
     button_display = button_display.push(Text::new(text_content));
 
-    if closeable {
+    if pinned {
+        button_display = button_display.push(Space::with_width(Length::Units(5))).push(get_icon('\u{F4EA}'));
+    }
+
+    if active && !moving {
+        button_display = button_display
+            .push(Space::with_width(Length::Units(5)))
+            .push(Button::new(get_icon(if pinned { '\u{F4E9}' } else { '\u{F4EA}' })).padding(0).on_press(TogglePinTab(tab_id.clone())))
+            .push(Space::with_width(Length::Units(5)))
+            .push(Button::new(get_icon('\u{F3FE}')).padding(0).on_press(TabMoveStarted(tab_id.clone())));
+    }
+
+    if closeable && !pinned {
         button_display = button_display.push(Space::with_width(Length::Units(5))).push(
             Button::new(Svg::new(svg::Handle::from_memory(CLOSE_ICON)).width(Length::Shrink)).on_press(CloseShelf(tab_id.clone()))
         )
@@ -138,53 +269,64 @@ pub(crate) fn create_tab<'a>(tab_id: TabId, text_content: String, closeable: boo
     )
     .width(Shrink)
     .height(Length::Units(30))
-        .on_press(TabSelected(tab_id))
+    .style(theme::Button::Custom(Box::new(Tab { active })))
+        .on_press(if moving { TabMovedTo(tab_id) } else { TabSelected(tab_id) })
 }
 
 
-pub(crate) async fn read_shelves(rpc: Arc<Mutex<Option<SimsFrontendClient<Channel>>>>, shelf_id: Option<String>, username: String, token: String) -> Result<Shelves, RpcCallResult> {
+pub(crate) async fn read_shelves(rpc: SharedClient, shelf_id: Option<String>) -> Result<Shelves, RpcCallResult> {
+    match rpc.lock().await.as_mut() {
+        None => Err(RpcCallResult::NotConnected),
+        Some(client_rpc) => {
+            let shelves = match shelf_id {
+                None => client_rpc.shelves().await?,
+                // a shelf that is gone reads as an empty list, the same as the server answers
+                Some(shelf_id) => match client_rpc.shelf(&shelf_id).await {
+                    Ok(shelf) => vec![shelf],
+                    Err(ClientError::NotFound(_)) => Vec::new(),
+                    Err(e) => return Err(e.into())
+                }
+            };
+            Ok(Shelves { shelves: shelves.into_iter().map(ShelfInfo::from).collect() })
+        }
+    }
+}
+
+pub(crate) async fn read_items(rpc: SharedClient, shelf_id: Option<String>) -> Result<GetItemsResponse, RpcCallResult> {
     match rpc.lock().await.as_mut() {
-        None => return Err(RpcCallResult::NotConnected),
-        Some(client_rpc) => client_rpc.get_shelves(GetShelvesRequest{
-            shelf_id,
-            username: username.to_owned(),
-            token: token.to_owned()})
-            .await
-            .map_err(|e|RpcCallResult::CallFailed(e.to_string())).map(|r|r.into_inner()),
+        None => Err(RpcCallResult::NotConnected),
+        Some(client_rpc) => {
+            let items = Items { items: client_rpc.items(shelf_id.as_deref()).await?.into_iter().map(ItemInfo::from).collect() };
+            Ok(match shelf_id {
+                Some(shelf) => GetItemsResponse::ShelfItems(shelf, items),
+                None => GetItemsResponse::AllItems(items)
+            })
+        }
     }
 }
 
-pub(crate) async fn read_items(rpc: Arc<Mutex<Option<SimsFrontendClient<Channel>>>>, shelf_id: Option<String>, username: String, token: String) -> Result<GetItemsResponse, RpcCallResult> {
+#[cfg(feature = "cli")]
+pub(crate) async fn read_item(rpc: SharedClient, item_id: u32) -> Result<ItemInfo, RpcCallResult> {
     match rpc.lock().await.as_mut() {
-        None => return Err(RpcCallResult::NotConnected),
-        Some(client_rpc) => client_rpc.get_items(GetItemsRequest{
-            shelf_id: shelf_id.clone(),
-            username: username.to_owned(),
-            token: token.to_owned()})
-            .await
-            .map_err(|e|RpcCallResult::CallFailed(e.to_string())).map(|r|
-            match shelf_id {
-                Some(shelf)=> GetItemsResponse::ShelfItems(shelf, r.into_inner()),
-                None => GetItemsResponse::AllItems(r.into_inner())
-            }),
+        None => Err(RpcCallResult::NotConnected),
+        Some(client_rpc) => client_rpc.item(item_id).await.map(ItemInfo::from).map_err(RpcCallResult::from)
     }
 }
 
-pub(crate) async fn create_shelf(rpc: Arc<Mutex<Option<SimsFrontendClient<Channel>>>>, shelf_id: String, num_slots: u32, username: String, token: String) -> Result<ActionApproved, RpcCallResult> {
+pub(crate) async fn create_shelf(rpc: SharedClient, shelf_id: String, num_slots: u32) -> Result<(), RpcCallResult> {
     match rpc.lock().await.as_mut() {
-        None => return Err(RpcCallResult::NotConnected),
-        Some(client_rpc) => client_rpc.create_shelf(CreateShelfRequest{
-            username,
-            token,
-            shelfinfo: Some(ShelfInfo{ shelf_id, shelf_count: num_slots }),
-        }).await.map_err(|e|RpcCallResult::CallFailed(e.to_string())).map(|r|r.into_inner())
-    }
-}
-
-pub(crate) async fn create_item(rpc: Arc<Mutex<Option<SimsFrontendClient<Channel>>>>, shelf_id: String, count: u32, username: String, token: String, item_name: String) -> Result<(), RpcCallResult> {
-    // match rpc.lock().await.as_mut() {
-    //     None => Err(RpcCallResult::NotConnected),
-    //     Some(client_rpc) => client_rpc.crea
-    // }
-    unimplemented!()
-}
\ No newline at end of file
+        None => Err(RpcCallResult::NotConnected),
+        Some(client_rpc) => client_rpc.create_shelf(&shelf_id, num_slots).await.map_err(RpcCallResult::from)
+    }
+}
+
+// the GUI works on the wire types, the layout itself lives in the library
+pub(crate) fn shelf_slots(shelf: &ShelfInfo, items: &[ItemInfo], capacity: u32) -> Vec<SlotInfo> {
+    let items = items.iter().cloned().map(Item::from).collect::<Vec<Item>>();
+    Shelf::from(shelf.clone()).layout_slots(&items, capacity).into_iter().map(SlotInfo::from).collect()
+}
+
+#[cfg(any(feature = "gui", feature = "tui"))]
+pub(crate) fn slot_fill(slots: &[SlotInfo]) -> f32 {
+    cs4471_sims_cli_client::slot_fill(&slots.iter().cloned().map(Slot::from).collect::<Vec<Slot>>())
+}
@@ -0,0 +1,212 @@
+use std::env;
+#[cfg(any(feature = "gui", feature = "tui"))]
+use std::fmt::{Display, Formatter};
+use std::sync::RwLock;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_bundle::bundle::FluentBundle;
+use intl_memoizer::concurrent::IntlLangMemoizer;
+use log::warn;
+use once_cell::sync::Lazy;
+use unic_langid::LanguageIdentifier;
+#[cfg(any(feature = "gui", feature = "tui"))]
+use crate::money::locale_separators;
+
+pub(crate) const DEFAULT_LOCALE: &str = "en-US";
+const LOCALE_VARIABLE: &str = "SIMS_LOCALE";
+
+// (language tag, native name, catalog), the first entry is the fallback for missing messages
+pub(crate) const CATALOGS: &[(&str, &str, &str)] = &[
+    ("en-US", "English", include_str!("../locales/en-US/sims.ftl")),
+    ("fr-FR", "Français", include_str!("../locales/fr-FR/sims.ftl")),
+];
+
+type Bundle = FluentBundle<FluentResource, IntlLangMemoizer>;
+
+struct Localizer {
+    locale: &'static str,
+    bundle: Bundle,
+    fallback: Bundle
+}
+
+static LOCALIZER: Lazy<RwLock<Localizer>> = Lazy::new(|| RwLock::new(Localizer::new(negotiate(&system_locale()))));
+
+#[cfg(any(feature = "gui", feature = "tui"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LocaleChoice {
+    System,
+    Locale(&'static str)
+}
+
+#[cfg(any(feature = "gui", feature = "tui"))]
+impl LocaleChoice {
+    pub(crate) fn from_tag(tag: Option<&str>) -> LocaleChoice {
+        match tag {
+            Some(tag) => LocaleChoice::Locale(negotiate(tag)),
+            None => LocaleChoice::System
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn tag(&self) -> Option<String> {
+        match self {
+            LocaleChoice::System => None,
+            LocaleChoice::Locale(tag) => Some(tag.to_string())
+        }
+    }
+
+    pub(crate) fn resolve(&self) -> &'static str {
+        match self {
+            LocaleChoice::System => negotiate(&system_locale()),
+            LocaleChoice::Locale(tag) => tag
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn all() -> Vec<LocaleChoice> {
+        std::iter::once(LocaleChoice::System)
+            .chain(CATALOGS.iter().map(|(tag, _, _)| LocaleChoice::Locale(*tag)))
+            .collect()
+    }
+}
+
+#[cfg(any(feature = "gui", feature = "tui"))]
+impl Display for LocaleChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocaleChoice::System => write!(f, "{}", tr("locale-system")),
+            LocaleChoice::Locale(tag) => {
+                let name = CATALOGS.iter().find(|(t, _, _)| t == tag).map(|(_, name, _)| *name).unwrap_or(*tag);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl Localizer {
+    fn new(locale: &'static str) -> Localizer {
+        Localizer {
+            locale,
+            bundle: build_bundle(locale),
+            fallback: build_bundle(DEFAULT_LOCALE)
+        }
+    }
+
+    fn format(&self, key: &str, args: Option<&FluentArgs>) -> String {
+        for bundle in [&self.bundle, &self.fallback] {
+            if let Some(pattern) = bundle.get_message(key).and_then(|m| m.value()) {
+                let mut errors = Vec::new();
+                let value = bundle.format_pattern(pattern, args, &mut errors);
+                if !errors.is_empty() {
+                    warn!("Errors formatting {} for {}: {:?}", key, self.locale, errors);
+                }
+                return value.into_owned();
+            }
+        }
+
+        warn!("Missing translation for {}", key);
+        key.to_owned()
+    }
+}
+
+fn catalog(locale: &str) -> &'static str {
+    CATALOGS.iter().find(|(tag, _, _)| *tag == locale).map(|(_, _, c)| *c).unwrap_or(CATALOGS[0].2)
+}
+
+fn build_bundle(locale: &'static str) -> Bundle {
+    let language: LanguageIdentifier = locale.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // iced draws the bidi isolation marks as boxes, the catalogs don't mix text directions anyway
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(catalog(locale).to_owned()).unwrap_or_else(|(resource, errors)| {
+        warn!("Catalog {} has syntax errors: {:?}", locale, errors);
+        resource
+    });
+    if let Err(errors) = bundle.add_resource(resource) {
+        warn!("Catalog {} has duplicate messages: {:?}", locale, errors);
+    }
+    bundle
+}
+
+// SIMS_LOCALE wins over the OS setting so a shared workstation can be switched per shell
+pub(crate) fn system_locale() -> String {
+    env::var(LOCALE_VARIABLE)
+        .ok()
+        .filter(|l| !l.is_empty())
+        .or_else(sys_locale::get_locale)
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_owned())
+}
+
+// picks the shipped catalog with the same language, so fr-CA and fr_BE.UTF-8 both get French
+pub(crate) fn negotiate(requested: &str) -> &'static str {
+    let language = requested.split(|c| c == '_' || c == '-' || c == '.').next().unwrap_or("").to_lowercase();
+    CATALOGS
+        .iter()
+        .find(|(tag, _, _)| tag.eq_ignore_ascii_case(&requested.replace('_', "-")))
+        .or_else(|| CATALOGS.iter().find(|(tag, _, _)| tag.split('-').next() == Some(language.as_str())))
+        .map(|(tag, _, _)| *tag)
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+#[cfg(any(feature = "gui", feature = "tui"))]
+pub(crate) fn set_locale(locale: &'static str) {
+    let mut localizer = LOCALIZER.write().unwrap();
+    if localizer.locale != locale {
+        *localizer = Localizer::new(locale);
+    }
+}
+
+#[cfg(any(feature = "gui", feature = "tui"))]
+pub(crate) fn current_locale() -> &'static str {
+    LOCALIZER.read().unwrap().locale
+}
+
+pub(crate) fn tr(key: &str) -> String {
+    LOCALIZER.read().unwrap().format(key, None)
+}
+
+pub(crate) fn tr_args(key: &str, args: &[(&str, FluentValue)]) -> String {
+    let args = args.iter().cloned().collect::<FluentArgs>();
+    LOCALIZER.read().unwrap().format(key, Some(&args))
+}
+
+// whole numbers with the current locale's digit grouping
+#[cfg(any(feature = "gui", feature = "tui"))]
+pub(crate) fn format_number(value: u64) -> String {
+    let (thousands_separator, _, _) = locale_separators(current_locale());
+    let digits = value.to_string();
+    let mut number = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            number.push(thousands_separator);
+        }
+        number.push(c);
+    }
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use fluent_syntax::ast::Entry;
+    use fluent_syntax::parser::parse;
+    use super::*;
+
+    fn message_keys(catalog: &str) -> BTreeSet<&str> {
+        let resource = parse(catalog).unwrap_or_else(|(_, errors)| panic!("catalog does not parse: {:?}", errors));
+        resource.body.into_iter().filter_map(|entry| match entry {
+            Entry::Message(message) => Some(message.id.name),
+            _ => None
+        }).collect()
+    }
+
+    #[test]
+    fn every_catalog_has_every_key() {
+        let all_keys = CATALOGS.iter().flat_map(|(_, _, c)| message_keys(c)).collect::<BTreeSet<&str>>();
+
+        for (tag, _, catalog) in CATALOGS {
+            let missing = all_keys.difference(&message_keys(catalog)).cloned().collect::<Vec<&str>>();
+            assert!(missing.is_empty(), "{} is missing {:?}", tag, missing);
+        }
+    }
+}
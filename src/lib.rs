@@ -0,0 +1,27 @@
+//! Client library for the SIMS inventory management service.
+//!
+//! [`SimsClient`] wraps the generated gRPC client with typed async methods for logging in,
+//! reading shelves and items and creating shelves. Results come back as the domain types in [`types`],
+//! the raw prost messages are still available in [`proto`] for anything the client does not cover.
+//!
+//! The GUI, command line and terminal frontends in this package are built on this API.
+//!
+//! # Features
+//!
+//! - `gui`, `cli` and `tui` (all on by default) only affect the binary. The library never needs
+//!   them, so `default-features = false` gives a dependency without iced or any terminal crates.
+//! - `mock-server` adds [`mock::MockServer`], an in-process server for tests and demos.
+
+pub mod client;
+#[cfg(feature = "mock-server")]
+pub mod mock;
+pub mod types;
+
+/// Messages and the raw tonic client generated from the SIMS protobuf definitions.
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("sims_ims_frontend");
+}
+
+pub use client::{ClientError, SimsClient, TlsOptions};
+pub use types::{DEFAULT_SLOT_CAPACITY, Item, Shelf, Slot, slot_fill};
@@ -0,0 +1,243 @@
+//! An in-process SIMS server with an in-memory inventory, for tests and demos.
+//!
+//! Only built with the `mock-server` feature. It answers every `SimsFrontend` call the way
+//! [`SimsClient`](crate::SimsClient) expects, but keeps nothing on disk and is not meant to
+//! stand in for the real server beyond that.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+use tonic::transport::Server;
+use crate::proto::{ActionApproved, ClientAction, CreateShelfRequest, GetItemRequest, GetItemsRequest, GetShelvesRequest, ItemInfo, Items, LoginRequest, ShelfInfo, Shelves, Token};
+use crate::proto::sims_frontend_server::{SimsFrontend, SimsFrontendServer};
+use crate::types::{Item, Shelf};
+
+#[derive(Debug, Default)]
+struct Inventory {
+    passwords: HashMap<String, String>,
+    // token to username
+    tokens: HashMap<String, String>,
+    shelves: Vec<ShelfInfo>,
+    items: Vec<ItemInfo>,
+    next_item_id: u32,
+    next_token: u64
+}
+
+impl Inventory {
+    fn issue_token(&mut self, username: &str) -> String {
+        self.next_token += 1;
+        let token = format!("mock-{}-{}", username, self.next_token);
+        self.tokens.insert(token.clone(), username.to_owned());
+        token
+    }
+
+    fn check_token(&self, username: &str, token: &str) -> Result<(), Status> {
+        match self.tokens.get(token) {
+            Some(owner) if owner == username => Ok(()),
+            _ => Err(Status::unauthenticated("invalid token"))
+        }
+    }
+
+    fn add_item(&mut self, mut item: ItemInfo) -> Result<u32, Status> {
+        if !self.shelves.iter().any(|s| s.shelf_id == item.shelf_id) {
+            return Err(Status::not_found(format!("shelf {}", item.shelf_id)));
+        }
+        self.next_item_id += 1;
+        item.object_id = self.next_item_id;
+        self.items.push(item);
+        Ok(self.next_item_id)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct MockSims {
+    inventory: Arc<Mutex<Inventory>>
+}
+
+impl MockSims {
+    fn inventory(&self) -> MutexGuard<'_, Inventory> {
+        // a panic in another handler doesn't leave the maps half written, so keep serving
+        self.inventory.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[tonic::async_trait]
+impl SimsFrontend for MockSims {
+    async fn cred_auth(&self, request: Request<LoginRequest>) -> Result<Response<Token>, Status> {
+        let login = request.into_inner();
+        let mut inventory = self.inventory();
+        match inventory.passwords.get(&login.username) {
+            Some(password) if password == &login.password => Ok(Response::new(Token { token: inventory.issue_token(&login.username) })),
+            _ => Err(Status::unauthenticated("wrong username or password"))
+        }
+    }
+
+    async fn sign_up(&self, request: Request<LoginRequest>) -> Result<Response<ActionApproved>, Status> {
+        let login = request.into_inner();
+        let mut inventory = self.inventory();
+        if login.username.is_empty() || login.password.is_empty() {
+            return Err(Status::permission_denied("username and password can't be empty"));
+        }
+        if inventory.passwords.contains_key(&login.username) {
+            return Err(Status::already_exists(format!("user {}", login.username)));
+        }
+        inventory.passwords.insert(login.username, login.password);
+        Ok(Response::new(ActionApproved {}))
+    }
+
+    // the protocol defines no ClientCmd actions, so every one is refused the way an unknown action is
+    async fn client_cmd(&self, request: Request<ClientAction>) -> Result<Response<ActionApproved>, Status> {
+        let action = request.into_inner();
+        if !self.inventory().passwords.contains_key(&action.user_id) {
+            return Err(Status::unauthenticated(format!("unknown user {}", action.user_id)));
+        }
+        Err(Status::invalid_argument(format!("unknown action {}", action.action)))
+    }
+
+    async fn get_shelves(&self, request: Request<GetShelvesRequest>) -> Result<Response<Shelves>, Status> {
+        let request = request.into_inner();
+        let inventory = self.inventory();
+        inventory.check_token(&request.username, &request.token)?;
+        let shelves = inventory.shelves.iter()
+            .filter(|s| request.shelf_id.as_ref().map(|id| id == &s.shelf_id).unwrap_or(true))
+            .cloned()
+            .collect();
+        Ok(Response::new(Shelves { shelves }))
+    }
+
+    async fn get_items(&self, request: Request<GetItemsRequest>) -> Result<Response<Items>, Status> {
+        let request = request.into_inner();
+        let inventory = self.inventory();
+        inventory.check_token(&request.username, &request.token)?;
+        let items = inventory.items.iter()
+            .filter(|i| request.shelf_id.as_ref().map(|id| id == &i.shelf_id).unwrap_or(true))
+            .cloned()
+            .collect();
+        Ok(Response::new(Items { items }))
+    }
+
+    async fn get_singular_item(&self, request: Request<GetItemRequest>) -> Result<Response<ItemInfo>, Status> {
+        let request = request.into_inner();
+        let inventory = self.inventory();
+        inventory.check_token(&request.username, &request.token)?;
+        inventory.items.iter()
+            .find(|i| i.object_id == request.item_id)
+            .cloned()
+            .map(Response::new)
+            .ok_or_else(|| Status::not_found(format!("item {}", request.item_id)))
+    }
+
+    async fn create_shelf(&self, request: Request<CreateShelfRequest>) -> Result<Response<ActionApproved>, Status> {
+        let request = request.into_inner();
+        let mut inventory = self.inventory();
+        inventory.check_token(&request.username, &request.token)?;
+        let shelf = request.shelfinfo.ok_or_else(|| Status::invalid_argument("no shelf info"))?;
+        if shelf.shelf_id.is_empty() || shelf.shelf_count == 0 {
+            return Err(Status::invalid_argument("a shelf needs an id and at least one slot"));
+        }
+        if inventory.shelves.iter().any(|s| s.shelf_id == shelf.shelf_id) {
+            return Err(Status::already_exists(format!("shelf {}", shelf.shelf_id)));
+        }
+        inventory.shelves.push(shelf);
+        Ok(Response::new(ActionApproved {}))
+    }
+}
+
+/// A running mock server. It stops when dropped.
+///
+/// Starting it spawns onto the current tokio runtime, so it has to be called from inside one.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use cs4471_sims_cli_client::{SimsClient, TlsOptions};
+/// use cs4471_sims_cli_client::mock::MockServer;
+///
+/// let server = MockServer::start().await?;
+/// server.add_user("alice", "secret");
+/// server.add_shelf("A1", 4);
+///
+/// let mut client = SimsClient::connect(server.address(), TlsOptions::default()).await?;
+/// client.login("alice", "secret").await?;
+/// assert_eq!(client.shelves().await?.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MockServer {
+    address: SocketAddr,
+    service: MockSims,
+    shutdown: Option<oneshot::Sender<()>>
+}
+
+impl MockServer {
+    /// Starts an empty server on a free port of the loopback interface.
+    pub async fn start() -> io::Result<MockServer> {
+        MockServer::start_on(SocketAddr::from(([127, 0, 0, 1], 0))).await
+    }
+
+    /// Starts an empty server listening on `address`, e.g. a fixed port for a demo.
+    pub async fn start_on(address: SocketAddr) -> io::Result<MockServer> {
+        let listener = TcpListener::bind(address).await?;
+        let address = listener.local_addr()?;
+        let service = MockSims::default();
+        let (shutdown, signal) = oneshot::channel::<()>();
+
+        let server = Server::builder().add_service(SimsFrontendServer::new(service.clone()));
+        tokio::spawn(async move {
+            let stopped = server.serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                let _ = signal.await;
+            }).await;
+            if let Err(e) = stopped {
+                log::warn!("Mock server stopped: {}", e);
+            }
+        });
+
+        Ok(MockServer { address, service, shutdown: Some(shutdown) })
+    }
+
+    /// The address to hand to [`SimsClient::connect`](crate::SimsClient::connect).
+    pub fn address(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Registers an account without going through sign up.
+    pub fn add_user(&self, username: &str, password: &str) {
+        self.service.inventory().passwords.insert(username.to_owned(), password.to_owned());
+    }
+
+    /// Adds a shelf, replacing one with the same id.
+    pub fn add_shelf(&self, shelf_id: &str, slots: u32) {
+        let mut inventory = self.service.inventory();
+        inventory.shelves.retain(|s| s.shelf_id != shelf_id);
+        inventory.shelves.push(Shelf { id: shelf_id.to_owned(), slots }.into());
+    }
+
+    /// Adds an item to an existing shelf and returns the id it was given, or `None` if there is no such shelf.
+    pub fn add_item(&self, shelf_id: &str, name: &str, stock: u32, price: u32) -> Option<u32> {
+        let item = Item { id: 0, name: name.to_owned(), shelf_id: shelf_id.to_owned(), stock, price };
+        self.service.inventory().add_item(item.into()).ok()
+    }
+
+    /// Every shelf as the server currently holds it.
+    pub fn shelves(&self) -> Vec<Shelf> {
+        self.service.inventory().shelves.iter().cloned().map(Shelf::from).collect()
+    }
+
+    /// Every item as the server currently holds it.
+    pub fn items(&self) -> Vec<Item> {
+        self.service.inventory().items.iter().cloned().map(Item::from).collect()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::env;
+
+const DEFAULT_CURRENCY: &str = "CAD";
+const DEFAULT_SCALE: u32 = 2;
+
+// Prices travel over the wire as an integer count of minor units (cents for a scale of 2)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MoneyFormat {
+    pub(crate) currency: String,
+    pub(crate) symbol: String,
+    pub(crate) symbol_after: bool,
+    pub(crate) scale: u32,
+    pub(crate) thousands_separator: char,
+    pub(crate) decimal_separator: char
+}
+
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        MoneyFormat::for_locale("en", DEFAULT_CURRENCY, DEFAULT_SCALE)
+    }
+}
+
+impl MoneyFormat {
+    pub(crate) fn for_locale(locale: &str, currency: &str, scale: u32) -> Self {
+        let (thousands_separator, decimal_separator, symbol_after) = locale_separators(locale);
+
+        MoneyFormat {
+            currency: currency.to_uppercase(),
+            symbol: currency_symbol(currency),
+            symbol_after,
+            scale,
+            thousands_separator,
+            decimal_separator
+        }
+    }
+
+    // same currency, separators for another locale
+    #[cfg(feature = "gui")]
+    pub(crate) fn with_locale(&self, locale: &str) -> Self {
+        MoneyFormat::for_locale(locale, &self.currency, self.scale)
+    }
+
+    // SIMS_CURRENCY and SIMS_CURRENCY_SCALE override the defaults, separators follow the system locale
+    pub(crate) fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_MONETARY", "LANG"]
+            .iter()
+            .filter_map(|v| env::var(v).ok())
+            .find(|v| !v.is_empty())
+            .unwrap_or_else(|| "en".to_owned());
+        let currency = env::var("SIMS_CURRENCY").unwrap_or_else(|_| DEFAULT_CURRENCY.to_owned());
+        let scale = env::var("SIMS_CURRENCY_SCALE").ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|s| *s <= 9)
+            .unwrap_or(DEFAULT_SCALE);
+
+        MoneyFormat::for_locale(&locale, &currency, scale)
+    }
+
+    pub(crate) fn format(&self, amount: u64) -> String {
+        let number = self.format_number(amount);
+        if self.symbol_after {
+            format!("{}\u{A0}{}", number, self.symbol)
+        } else {
+            format!("{}{}", self.symbol, number)
+        }
+    }
+
+    fn format_number(&self, amount: u64) -> String {
+        let divisor = 10u64.pow(self.scale);
+        let major = (amount / divisor).to_string();
+        let minor = amount % divisor;
+
+        let mut number = String::new();
+        for (i, c) in major.chars().enumerate() {
+            if i > 0 && (major.len() - i) % 3 == 0 {
+                number.push(self.thousands_separator);
+            }
+            number.push(c);
+        }
+
+        if self.scale > 0 {
+            number.push(self.decimal_separator);
+            number.push_str(&format!("{:0width$}", minor, width = self.scale as usize));
+        }
+        number
+    }
+}
+
+// (thousands separator, decimal separator, currency symbol after the amount)
+pub(crate) fn locale_separators(locale: &str) -> (char, char, bool) {
+    let language = locale.split(|c| c == '_' || c == '-' || c == '.').next().unwrap_or("").to_lowercase();
+    match language.as_str() {
+        "fr" => ('\u{202F}', ',', true),
+        "de" | "es" | "it" | "nl" | "pt" | "da" => ('.', ',', true),
+        "sv" | "nb" | "fi" | "pl" | "cs" | "ru" => ('\u{A0}', ',', true),
+        _ => (',', '.', false)
+    }
+}
+
+fn currency_symbol(currency: &str) -> String {
+    match currency.to_uppercase().as_str() {
+        "CAD" | "USD" | "AUD" | "NZD" | "MXN" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        "KRW" => "₩",
+        other => return other.to_owned()
+    }.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_with_the_locale_separators() {
+        assert_eq!(MoneyFormat::for_locale("en_CA.UTF-8", "CAD", 2).format(123_456), "$1,234.56");
+        assert_eq!(MoneyFormat::for_locale("de", "EUR", 2).format(123_456), "1.234,56\u{A0}€");
+        assert_eq!(MoneyFormat::for_locale("fr-FR", "CAD", 2).format(123_456), "1\u{202F}234,56\u{A0}$");
+    }
+
+    #[test]
+    fn minor_units_follow_the_scale() {
+        assert_eq!(MoneyFormat::for_locale("en", "JPY", 0).format(1_500), "¥1,500");
+        assert_eq!(MoneyFormat::for_locale("en", "CAD", 2).format(5), "$0.05");
+        assert_eq!(MoneyFormat::for_locale("en", "XYZ", 3).format(1_000_001), "XYZ1,000.001");
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+use linked_hash_set::LinkedHashSet;
+use crate::frontend::TabId;
+
+pub(crate) const MAX_HISTORY: usize = 50;
+
+// browser style history, visiting a tab after going back drops everything ahead of it
+#[derive(Debug, Default)]
+pub(crate) struct TabHistory {
+    entries: Vec<TabId>,
+    position: usize
+}
+
+impl TabHistory {
+    pub(crate) fn current(&self) -> &TabId {
+        self.entries.get(self.position).unwrap_or_default()
+    }
+
+    pub(crate) fn visit(&mut self, tab: TabId) {
+        if !self.entries.is_empty() {
+            if *self.current() == tab {
+                return;
+            }
+            self.entries.truncate(self.position + 1);
+        }
+
+        self.entries.push(tab);
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.drain(..self.entries.len() - MAX_HISTORY);
+        }
+        self.position = self.entries.len() - 1;
+    }
+
+    pub(crate) fn back(&mut self) -> Option<TabId> {
+        if !self.can_go_back() {
+            return None;
+        }
+        self.position -= 1;
+        Some(self.current().clone())
+    }
+
+    pub(crate) fn forward(&mut self) -> Option<TabId> {
+        if !self.can_go_forward() {
+            return None;
+        }
+        self.position += 1;
+        Some(self.current().clone())
+    }
+
+    pub(crate) fn can_go_back(&self) -> bool {
+        self.position > 0
+    }
+
+    pub(crate) fn can_go_forward(&self) -> bool {
+        self.position + 1 < self.entries.len()
+    }
+
+    // removes every visit to a closed tab, collapsing neighbours that become duplicates
+    pub(crate) fn remove(&mut self, tab: &TabId) {
+        let mut kept: Vec<TabId> = Vec::with_capacity(self.entries.len());
+        let mut position = 0;
+
+        for (i, entry) in self.entries.drain(..).enumerate() {
+            if entry != *tab && kept.last() != Some(&entry) {
+                kept.push(entry);
+            }
+            if i == self.position {
+                position = kept.len().saturating_sub(1);
+            }
+        }
+
+        self.entries = kept;
+        self.position = position;
+    }
+}
+
+// moves a tab in front of another, pinned tabs always stay ahead of unpinned ones
+pub(crate) fn reorder_tabs(tabs: &LinkedHashSet<TabId>, pinned: &HashSet<TabId>, moved: &TabId, target: &TabId) -> LinkedHashSet<TabId> {
+    let mut order = tabs.iter().cloned().collect::<Vec<TabId>>();
+    // the moved tab takes the target's place, so moving right past the last tab puts it last
+    if let (Some(from), Some(to)) = (order.iter().position(|t| t == moved), order.iter().position(|t| t == target)) {
+        let tab = order.remove(from);
+        order.insert(to, tab);
+    }
+
+    let (mut first, rest): (Vec<TabId>, Vec<TabId>) = order.into_iter().partition(|t| pinned.contains(t));
+    first.extend(rest);
+    first.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shelf(id: &str) -> TabId {
+        TabId::ShelfView(id.to_owned())
+    }
+
+    #[test]
+    fn visiting_after_going_back_drops_the_forward_entries() {
+        let mut history = TabHistory::default();
+        history.visit(TabId::AllShelves);
+        history.visit(shelf("A1"));
+        history.visit(shelf("B2"));
+
+        assert_eq!(history.back(), Some(shelf("A1")));
+        assert!(history.can_go_forward());
+        history.visit(TabId::AllItems);
+        assert!(!history.can_go_forward());
+        assert_eq!(history.forward(), None);
+
+        assert_eq!(history.back(), Some(shelf("A1")));
+        assert_eq!(history.back(), Some(TabId::AllShelves));
+        assert_eq!(history.back(), None);
+    }
+
+    #[test]
+    fn visiting_the_current_tab_adds_nothing() {
+        let mut history = TabHistory::default();
+        history.visit(shelf("A1"));
+        history.visit(shelf("A1"));
+
+        assert!(!history.can_go_back());
+        assert_eq!(history.current(), &shelf("A1"));
+    }
+
+    #[test]
+    fn only_the_latest_visits_are_kept() {
+        let mut history = TabHistory::default();
+        for i in 0..MAX_HISTORY + 10 {
+            history.visit(shelf(&i.to_string()));
+        }
+
+        let mut steps = 0;
+        while history.back().is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_HISTORY - 1);
+        assert_eq!(history.current(), &shelf("10"));
+    }
+
+    #[test]
+    fn removing_a_tab_collapses_the_visits_around_it() {
+        let mut history = TabHistory::default();
+        history.visit(TabId::AllShelves);
+        history.visit(shelf("A1"));
+        history.visit(TabId::AllShelves);
+        history.visit(shelf("B2"));
+
+        history.remove(&shelf("A1"));
+        assert_eq!(history.current(), &shelf("B2"));
+        assert_eq!(history.back(), Some(TabId::AllShelves));
+        assert_eq!(history.back(), None);
+    }
+
+    #[test]
+    fn removing_the_current_tab_moves_to_the_one_before() {
+        let mut history = TabHistory::default();
+        history.visit(TabId::AllShelves);
+        history.visit(shelf("A1"));
+
+        history.remove(&shelf("A1"));
+        assert_eq!(history.current(), &TabId::AllShelves);
+        assert!(!history.can_go_forward());
+    }
+
+    fn tabs(ids: &[&str]) -> LinkedHashSet<TabId> {
+        ids.iter().map(|id| shelf(id)).collect()
+    }
+
+    fn order(tabs: &LinkedHashSet<TabId>) -> Vec<TabId> {
+        tabs.iter().cloned().collect()
+    }
+
+    #[test]
+    fn a_tab_can_be_moved_to_the_last_place() {
+        let moved = reorder_tabs(&tabs(&["A1", "B2", "C3"]), &HashSet::new(), &shelf("A1"), &shelf("C3"));
+        assert_eq!(order(&moved), order(&tabs(&["B2", "C3", "A1"])));
+    }
+
+    #[test]
+    fn a_tab_can_be_moved_to_the_first_place() {
+        let moved = reorder_tabs(&tabs(&["A1", "B2", "C3"]), &HashSet::new(), &shelf("C3"), &shelf("A1"));
+        assert_eq!(order(&moved), order(&tabs(&["C3", "A1", "B2"])));
+    }
+
+    #[test]
+    fn placing_a_tab_on_itself_keeps_the_order() {
+        let moved = reorder_tabs(&tabs(&["A1", "B2", "C3"]), &HashSet::new(), &shelf("B2"), &shelf("B2"));
+        assert_eq!(order(&moved), order(&tabs(&["A1", "B2", "C3"])));
+    }
+
+    #[test]
+    fn pinned_tabs_stay_in_front() {
+        let pinned = HashSet::from([shelf("C3")]);
+        let moved = reorder_tabs(&tabs(&["A1", "B2", "C3"]), &pinned, &shelf("C3"), &shelf("C3"));
+        assert_eq!(order(&moved), order(&tabs(&["C3", "A1", "B2"])));
+
+        let moved = reorder_tabs(&moved, &pinned, &shelf("A1"), &shelf("C3"));
+        assert_eq!(order(&moved), order(&tabs(&["C3", "A1", "B2"])));
+    }
+}
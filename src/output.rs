@@ -0,0 +1,358 @@
+use std::io::{self, Write};
+use clap::ValueEnum;
+use serde::Serialize;
+use crate::cli::CliError;
+use crate::frontend::sims_ims_frontend::{ItemInfo, ShelfInfo, SlotInfo};
+use crate::money::MoneyFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+    Yaml
+}
+
+// the serialized field names are what scripts depend on, they are listed in the README and must not change
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ShelfRecord {
+    pub(crate) shelf_id: String,
+    pub(crate) slots: u32
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ItemRecord {
+    pub(crate) item_id: u32,
+    pub(crate) name: String,
+    pub(crate) shelf_id: String,
+    pub(crate) stock: u32,
+    // minor currency units so no format has to agree on decimal separators
+    pub(crate) price: u32
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SlotRecord {
+    pub(crate) shelf_id: String,
+    pub(crate) slot: u32,
+    pub(crate) capacity: u32,
+    pub(crate) item_count: u32
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct IdentityRecord {
+    pub(crate) username: String,
+    pub(crate) server: String,
+    // RFC 3339, null when the token doesn't say
+    pub(crate) expires: Option<String>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TokenRecord {
+    pub(crate) username: String,
+    pub(crate) server: String,
+    pub(crate) token: String
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LineStatus {
+    Ok,
+    Valid,
+    Failed,
+    Skipped
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ScriptLineRecord {
+    pub(crate) line: usize,
+    pub(crate) command: String,
+    pub(crate) status: LineStatus,
+    pub(crate) message: String
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Fail,
+    Skip
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DoctorCheckRecord {
+    pub(crate) check: String,
+    pub(crate) status: CheckStatus,
+    pub(crate) millis: Option<u64>,
+    pub(crate) detail: String
+}
+
+// what a headless command produced, the shell and one-off commands print it while scripts only report it
+#[derive(Debug, Clone)]
+pub(crate) enum CommandOutput {
+    Message(String),
+    Token(Vec<TokenRecord>),
+    Shelves(Vec<ShelfRecord>),
+    Items(Vec<ItemRecord>),
+    Slots(Vec<SlotRecord>),
+    Identity(Vec<IdentityRecord>)
+}
+
+impl CommandOutput {
+    pub(crate) fn print(&self, format: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+        match self {
+            // confirmations go to stderr so stdout only ever carries data
+            CommandOutput::Message(message) => {
+                eprintln!("{}", message);
+                Ok(())
+            }
+            CommandOutput::Token(records) => print_records(records, format, money_format),
+            CommandOutput::Shelves(records) => print_records(records, format, money_format),
+            CommandOutput::Items(records) => print_records(records, format, money_format),
+            CommandOutput::Slots(records) => print_records(records, format, money_format),
+            CommandOutput::Identity(records) => print_records(records, format, money_format)
+        }
+    }
+
+    pub(crate) fn summary(&self) -> String {
+        match self {
+            CommandOutput::Message(message) => message.clone(),
+            CommandOutput::Token(_) => "signed in".to_owned(),
+            CommandOutput::Shelves(records) => format!("{} shelves", records.len()),
+            CommandOutput::Items(records) => match records.as_slice() {
+                [item] => format!("item {} has {} in stock", item.item_id, item.stock),
+                _ => format!("{} items", records.len())
+            },
+            CommandOutput::Slots(records) => format!("{} slots", records.len()),
+            CommandOutput::Identity(records) => records.iter().map(|r| format!("{} on {}", r.username, r.server)).collect()
+        }
+    }
+}
+
+impl From<&ShelfInfo> for ShelfRecord {
+    fn from(shelf: &ShelfInfo) -> Self {
+        ShelfRecord { shelf_id: shelf.shelf_id.clone(), slots: shelf.shelf_count }
+    }
+}
+
+impl From<&ItemInfo> for ItemRecord {
+    fn from(item: &ItemInfo) -> Self {
+        ItemRecord {
+            item_id: item.object_id,
+            name: item.description.clone(),
+            shelf_id: item.shelf_id.clone(),
+            stock: item.stock,
+            price: item.price
+        }
+    }
+}
+
+impl SlotRecord {
+    pub(crate) fn new(shelf_id: &str, slot: &SlotInfo) -> SlotRecord {
+        SlotRecord { shelf_id: shelf_id.to_owned(), slot: slot.slot_num, capacity: slot.capacity, item_count: slot.item_count }
+    }
+}
+
+// how a record is laid out in the human readable table, the other formats use the serde field names
+pub(crate) trait Record: Serialize {
+    const HEADERS: &'static [&'static str];
+    const FIELDS: &'static [&'static str];
+
+    fn cells(&self, money_format: &MoneyFormat) -> Vec<String>;
+}
+
+impl Record for ShelfRecord {
+    const HEADERS: &'static [&'static str] = &["SHELF", "SLOTS"];
+    const FIELDS: &'static [&'static str] = &["shelf_id", "slots"];
+
+    fn cells(&self, _money_format: &MoneyFormat) -> Vec<String> {
+        vec![self.shelf_id.clone(), self.slots.to_string()]
+    }
+}
+
+impl Record for ItemRecord {
+    const HEADERS: &'static [&'static str] = &["ID", "NAME", "SHELF", "STOCK", "PRICE"];
+    const FIELDS: &'static [&'static str] = &["item_id", "name", "shelf_id", "stock", "price"];
+
+    fn cells(&self, money_format: &MoneyFormat) -> Vec<String> {
+        vec![
+            self.item_id.to_string(),
+            self.name.clone(),
+            self.shelf_id.clone(),
+            self.stock.to_string(),
+            money_format.format(self.price as u64)
+        ]
+    }
+}
+
+impl Record for SlotRecord {
+    const HEADERS: &'static [&'static str] = &["SHELF", "SLOT", "CAPACITY", "ITEMS"];
+    const FIELDS: &'static [&'static str] = &["shelf_id", "slot", "capacity", "item_count"];
+
+    fn cells(&self, _money_format: &MoneyFormat) -> Vec<String> {
+        vec![self.shelf_id.clone(), self.slot.to_string(), self.capacity.to_string(), self.item_count.to_string()]
+    }
+}
+
+impl Record for IdentityRecord {
+    const HEADERS: &'static [&'static str] = &["USERNAME", "SERVER", "EXPIRES"];
+    const FIELDS: &'static [&'static str] = &["username", "server", "expires"];
+
+    fn cells(&self, _money_format: &MoneyFormat) -> Vec<String> {
+        vec![self.username.clone(), self.server.clone(), self.expires.clone().unwrap_or_else(|| "unknown".to_owned())]
+    }
+}
+
+impl Record for TokenRecord {
+    const HEADERS: &'static [&'static str] = &["USERNAME", "SERVER", "TOKEN"];
+    const FIELDS: &'static [&'static str] = &["username", "server", "token"];
+
+    fn cells(&self, _money_format: &MoneyFormat) -> Vec<String> {
+        vec![self.username.clone(), self.server.clone(), self.token.clone()]
+    }
+}
+
+impl Record for ScriptLineRecord {
+    const HEADERS: &'static [&'static str] = &["LINE", "STATUS", "COMMAND", "MESSAGE"];
+    const FIELDS: &'static [&'static str] = &["line", "command", "status", "message"];
+
+    fn cells(&self, _money_format: &MoneyFormat) -> Vec<String> {
+        let status = match self.status {
+            LineStatus::Ok => "ok",
+            LineStatus::Valid => "valid",
+            LineStatus::Failed => "failed",
+            LineStatus::Skipped => "skipped"
+        };
+        vec![self.line.to_string(), status.to_owned(), self.command.clone(), self.message.clone()]
+    }
+}
+
+impl Record for DoctorCheckRecord {
+    const HEADERS: &'static [&'static str] = &["CHECK", "STATUS", "TIME", "DETAIL"];
+    const FIELDS: &'static [&'static str] = &["check", "status", "millis", "detail"];
+
+    fn cells(&self, _money_format: &MoneyFormat) -> Vec<String> {
+        let status = match self.status {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skip => "skip"
+        };
+        let time = self.millis.map(|m| format!("{} ms", m)).unwrap_or_default();
+        vec![self.check.clone(), status.to_owned(), time, self.detail.clone()]
+    }
+}
+
+// every format prints a list, even for a single item, so consumers never have to special case one result
+pub(crate) fn print_records<R: Record>(records: &[R], format: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+    let stdout = io::stdout();
+    write_records(&mut stdout.lock(), records, format, money_format).map_err(|e| CliError::Output(e.to_string()))
+}
+
+fn write_records<R: Record>(out: &mut impl Write, records: &[R], format: OutputFormat, money_format: &MoneyFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Table => write_table(out, R::HEADERS, records.iter().map(|r| r.cells(money_format)).collect()),
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut *out, records)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(out)),
+        OutputFormat::Ndjson => records.iter().try_for_each(|r| {
+            serde_json::to_writer(&mut *out, r).map_err(io::Error::from)?;
+            writeln!(out)
+        }),
+        OutputFormat::Csv => write_csv(out, records),
+        OutputFormat::Yaml => serde_yaml::to_writer(out, records).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+fn write_csv<R: Record>(out: &mut impl Write, records: &[R]) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    if records.is_empty() {
+        // serde only learns the header from the first record, an empty list still gets one
+        writer.write_record(R::FIELDS)?;
+    }
+    for record in records {
+        writer.serialize(record)?;
+    }
+    writer.flush()
+}
+
+// columns are padded to the widest cell so the output lines up in a terminal
+fn write_table(out: &mut impl Write, headers: &[&str], rows: Vec<Vec<String>>) -> io::Result<()> {
+    let mut widths = headers.iter().map(|h| h.chars().count()).collect::<Vec<usize>>();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<String>| {
+        cells.iter().zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    writeln!(out, "{}", line(headers.iter().map(|h| h.to_string()).collect()))?;
+    for row in rows {
+        writeln!(out, "{}", line(row))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written<R: Record>(records: &[R], format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_records(&mut out, records, format, &MoneyFormat::default()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn token() -> TokenRecord {
+        TokenRecord { username: "alice".to_owned(), server: "http://localhost:50051".to_owned(), token: "secret-token".to_owned() }
+    }
+
+    // serde writes the csv header from the struct, so it only matches FIELDS while the two agree
+    fn header_matches_fields<R: Record>(record: R) {
+        let csv = written(&[record], OutputFormat::Csv);
+        assert_eq!(csv.lines().next(), Some(R::FIELDS.join(",").as_str()));
+        assert_eq!(written::<R>(&[], OutputFormat::Csv).lines().next(), Some(R::FIELDS.join(",").as_str()));
+    }
+
+    #[test]
+    fn csv_headers_are_the_documented_field_names() {
+        header_matches_fields(ShelfRecord { shelf_id: "A1".to_owned(), slots: 4 });
+        header_matches_fields(ItemRecord { item_id: 1, name: "Soap".to_owned(), shelf_id: "A1".to_owned(), stock: 3, price: 249 });
+        header_matches_fields(SlotRecord { shelf_id: "A1".to_owned(), slot: 0, capacity: 30, item_count: 3 });
+        header_matches_fields(IdentityRecord { username: "alice".to_owned(), server: "http://localhost:50051".to_owned(), expires: None });
+        header_matches_fields(token());
+        header_matches_fields(ScriptLineRecord { line: 1, command: "shelves list".to_owned(), status: LineStatus::Ok, message: String::new() });
+        header_matches_fields(DoctorCheckRecord { check: "connect".to_owned(), status: CheckStatus::Pass, millis: Some(3), detail: String::new() });
+    }
+
+    #[test]
+    fn the_token_follows_the_output_format() {
+        let json = serde_json::from_str::<serde_json::Value>(&written(&[token()], OutputFormat::Json)).unwrap();
+        assert_eq!(json, serde_json::json!([{"username": "alice", "server": "http://localhost:50051", "token": "secret-token"}]));
+
+        let ndjson = written(&[token()], OutputFormat::Ndjson);
+        assert_eq!(ndjson.lines().count(), 1);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&ndjson).unwrap()["token"], "secret-token");
+
+        assert_eq!(written(&[token()], OutputFormat::Csv), "username,server,token\nalice,http://localhost:50051,secret-token\n");
+
+        let table = written(&[token()], OutputFormat::Table);
+        assert_eq!(table.lines().next().map(|l| l.split_whitespace().collect::<Vec<&str>>()), Some(vec!["USERNAME", "SERVER", "TOKEN"]));
+        assert!(table.lines().nth(1).unwrap().ends_with("secret-token"));
+
+        let yaml = serde_yaml::from_str::<serde_yaml::Value>(&written(&[token()], OutputFormat::Yaml)).unwrap();
+        assert_eq!(yaml[0]["token"], serde_yaml::Value::from("secret-token"));
+    }
+
+    #[test]
+    fn statuses_are_lowercase() {
+        let json = written(&[ScriptLineRecord { line: 2, command: "items get 9".to_owned(), status: LineStatus::Failed, message: "item 9 not found".to_owned() }], OutputFormat::Json);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&json).unwrap()[0]["status"], "failed");
+    }
+}
@@ -0,0 +1,104 @@
+use iced::widget::text_input;
+use crate::app::ClientState;
+use crate::frontend::TabId;
+use crate::i18n::{tr, tr_args};
+use crate::ui_messages::Message;
+
+const MAX_ENTRIES: usize = 10;
+
+#[derive(Debug, Default)]
+pub(crate) struct CommandPalette {
+    pub(crate) query: String,
+    pub(crate) selected: usize
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PaletteEntry {
+    pub(crate) label: String,
+    pub(crate) message: Message
+}
+
+pub(crate) fn palette_input_id() -> text_input::Id {
+    text_input::Id::new("command-palette")
+}
+
+pub(crate) fn search_input_id() -> text_input::Id {
+    text_input::Id::new("item-search")
+}
+
+pub(crate) fn login_input_id() -> text_input::Id {
+    text_input::Id::new("login-username")
+}
+
+// first field of the new shelf form
+pub(crate) fn edit_input_id() -> text_input::Id {
+    text_input::Id::new("edit-first-field")
+}
+
+pub(crate) fn palette_entries(state: &ClientState, query: &str) -> Vec<PaletteEntry> {
+    let commands = vec![
+        (tr("command-new"), Message::StartEditing(state.new_edit_target())),
+        (tr("command-refresh"), state.refresh_message()),
+        (tr("command-go-shelves"), Message::TabSelected(TabId::AllShelves)),
+        (tr("command-go-items"), Message::TabSelected(TabId::AllItems)),
+        (tr("command-go-dashboard"), Message::TabSelected(TabId::Dashboard)),
+        (tr("command-suggestions"), Message::ShowSuggestions),
+        (tr("command-settings"), Message::ShowSettings),
+        (tr("command-account"), Message::ShowAccount),
+        (tr("command-logout"), Message::Logout),
+    ];
+
+    let shelves = state.shelves.iter().map(|s| {
+        (tr_args("palette-shelf", &[("shelf", s.shelf_id.as_str().into())]), Message::OpenShelf(TabId::ShelfView(s.shelf_id.clone())))
+    });
+
+    let items = state.all_items.values().flatten().map(|i| {
+        (tr_args("palette-item", &[
+            ("item", i.object_id.into()),
+            ("description", i.description.as_str().into()),
+            ("shelf", i.shelf_id.as_str().into())
+        ]), Message::OpenShelf(TabId::ShelfView(i.shelf_id.clone())))
+    });
+
+    let mut scored = commands
+        .into_iter()
+        .chain(shelves)
+        .chain(items)
+        .filter_map(|(label, message)| fuzzy_score(query, &label).map(|score| (score, PaletteEntry { label, message })))
+        .collect::<Vec<(i32, PaletteEntry)>>();
+
+    // stable sort keeps commands ahead of shelves and items on equal scores
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().take(MAX_ENTRIES).map(|(_, e)| e).collect()
+}
+
+// subsequence match, rewarding runs of consecutive characters and matches at the start of words
+pub(crate) fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate = candidate.to_lowercase().chars().collect::<Vec<char>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..candidate.len()).find(|i| candidate[*i] == q)?;
+
+        score += 1;
+        if previous_match.map_or(false, |p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position) as i32 / 4;
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use crate::i18n::{tr, tr_args};
+
+#[cfg(feature = "gui")]
+const COMMON_PASSWORDS: [&str; 12] = [
+    "password", "123456", "12345678", "qwerty", "abc123", "letmein",
+    "welcome", "admin", "iloveyou", "monkey", "dragon", "inventory"
+];
+
+// rules a new password has to meet, set per server in profiles.toml under [profile.password_policy]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct PasswordPolicy {
+    pub(crate) min_length: usize,
+    pub(crate) require_uppercase: bool,
+    pub(crate) require_lowercase: bool,
+    pub(crate) require_digit: bool,
+    pub(crate) require_symbol: bool,
+    pub(crate) reject_common: bool
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: true,
+            require_symbol: false,
+            reject_common: true
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PolicyRule {
+    MinLength(usize),
+    Uppercase,
+    Lowercase,
+    Digit,
+    Symbol,
+    NotCommon
+}
+
+#[cfg(feature = "gui")]
+impl PolicyRule {
+    pub(crate) fn description(&self) -> String {
+        match self {
+            PolicyRule::MinLength(length) => tr_args("rule-min-length", &[("length", (*length as u64).into())]),
+            PolicyRule::Uppercase => tr("rule-uppercase"),
+            PolicyRule::Lowercase => tr("rule-lowercase"),
+            PolicyRule::Digit => tr("rule-digit"),
+            PolicyRule::Symbol => tr("rule-symbol"),
+            PolicyRule::NotCommon => tr("rule-not-common")
+        }
+    }
+
+    pub(crate) fn is_met(&self, password: &str) -> bool {
+        match self {
+            PolicyRule::MinLength(length) => password.chars().count() >= *length,
+            PolicyRule::Uppercase => password.chars().any(|c| c.is_uppercase()),
+            PolicyRule::Lowercase => password.chars().any(|c| c.is_lowercase()),
+            PolicyRule::Digit => password.chars().any(|c| c.is_ascii_digit()),
+            PolicyRule::Symbol => password.chars().any(|c| !c.is_alphanumeric()),
+            PolicyRule::NotCommon => !is_common(password)
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl PasswordPolicy {
+    pub(crate) fn rules(&self) -> Vec<PolicyRule> {
+        let mut rules = vec![PolicyRule::MinLength(self.min_length)];
+        if self.require_uppercase {
+            rules.push(PolicyRule::Uppercase);
+        }
+        if self.require_lowercase {
+            rules.push(PolicyRule::Lowercase);
+        }
+        if self.require_digit {
+            rules.push(PolicyRule::Digit);
+        }
+        if self.require_symbol {
+            rules.push(PolicyRule::Symbol);
+        }
+        if self.reject_common {
+            rules.push(PolicyRule::NotCommon);
+        }
+        rules
+    }
+
+    pub(crate) fn is_satisfied(&self, password: &str) -> bool {
+        self.rules().iter().all(|r| r.is_met(password))
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Strength {
+    VeryWeak,
+    Weak,
+    Fair,
+    Strong,
+    VeryStrong
+}
+
+#[cfg(feature = "gui")]
+impl Strength {
+    pub(crate) fn label(&self) -> String {
+        tr(match self {
+            Strength::VeryWeak => "strength-very-weak",
+            Strength::Weak => "strength-weak",
+            Strength::Fair => "strength-fair",
+            Strength::Strong => "strength-strong",
+            Strength::VeryStrong => "strength-very-strong"
+        })
+    }
+
+    // position on the meter, out of 4
+    pub(crate) fn score(&self) -> f32 {
+        *self as u8 as f32
+    }
+}
+
+// rough entropy estimate from length and the character classes in use, common passwords score nothing
+#[cfg(feature = "gui")]
+pub(crate) fn strength(password: &str) -> Strength {
+    if password.is_empty() || is_common(password) {
+        return Strength::VeryWeak;
+    }
+
+    let mut pool = 0;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if password.chars().any(|c| !c.is_ascii_alphanumeric()) {
+        pool += 33;
+    }
+
+    // repeated characters add little, so only distinct ones count towards length
+    let mut distinct = password.chars().collect::<Vec<char>>();
+    distinct.sort_unstable();
+    distinct.dedup();
+    let bits = distinct.len() as f64 * (pool as f64).log2();
+
+    match bits as u32 {
+        0..=27 => Strength::VeryWeak,
+        28..=35 => Strength::Weak,
+        36..=59 => Strength::Fair,
+        60..=127 => Strength::Strong,
+        _ => Strength::VeryStrong
+    }
+}
+
+#[cfg(feature = "gui")]
+fn is_common(password: &str) -> bool {
+    let lowered = password.to_lowercase();
+    COMMON_PASSWORDS.iter().any(|p| lowered == *p || lowered.trim_end_matches(|c: char| c.is_ascii_digit()) == *p)
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_common_passwords_are_very_weak() {
+        for password in ["", "password", "Password1", "QWERTY123", "abc", "aaaaaaaaaaaaaaaa"] {
+            assert_eq!(strength(password), Strength::VeryWeak, "{}", password);
+        }
+    }
+
+    #[test]
+    fn more_character_classes_and_distinct_characters_score_higher() {
+        assert_eq!(strength("correcthorse"), Strength::Weak);
+        assert_eq!(strength("kitchen2019"), Strength::Fair);
+        assert_eq!(strength("Tr0ub4dor&3"), Strength::Strong);
+        assert_eq!(strength("Qx7#mP2$vL9!rT4&wZ8*"), Strength::VeryStrong);
+        assert!(strength("kitchen") < strength("Kitchen2019!"));
+    }
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::PathBuf;
+use cs4471_sims_cli_client::TlsOptions;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use crate::password::PasswordPolicy;
+use crate::session::project_dirs;
+
+const PROFILES_FILE: &str = "profiles.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TlsSettings {
+    pub(crate) enabled: bool,
+    pub(crate) ca_certificate: Option<PathBuf>,
+    pub(crate) domain: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Profile {
+    pub(crate) name: String,
+    pub(crate) server: String,
+    pub(crate) username: String,
+    pub(crate) tls: TlsSettings,
+    pub(crate) theme: String,
+    pub(crate) password_policy: PasswordPolicy
+}
+
+impl From<TlsSettings> for TlsOptions {
+    fn from(tls: TlsSettings) -> Self {
+        TlsOptions { enabled: tls.enabled, ca_certificate: tls.ca_certificate, domain: tls.domain }
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            name: String::new(),
+            server: crate::SERVER_ADDRESS.to_owned(),
+            username: String::new(),
+            tls: TlsSettings::default(),
+            theme: "dark".to_owned(),
+            password_policy: PasswordPolicy::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    #[serde(default, rename = "profile")]
+    profiles: Vec<Profile>
+}
+
+pub(crate) fn profiles_path() -> Option<PathBuf> {
+    project_dirs().map(|d| d.config_dir().join(PROFILES_FILE))
+}
+
+// profiles are hand edited TOML, a broken file is reported and treated as empty so login still works
+pub(crate) fn load_profiles() -> Vec<Profile> {
+    let path = match profiles_path() {
+        Some(p) => p,
+        None => return Vec::new()
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => match toml::from_str::<ProfilesFile>(&contents) {
+            Ok(file) => file.profiles.into_iter().filter(|p| !p.name.is_empty()).collect(),
+            Err(e) => {
+                warn!("Could not read profiles from {:?}: {}", path, e);
+                Vec::new()
+            }
+        },
+        Err(_) => Vec::new()
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use crate::cli::{check_new_shelf, CliError, Command, Connection, execute, ItemCommand, list_items, ShelfCommand};
+use crate::frontend::read_shelves;
+use crate::frontend::sims_ims_frontend::ItemInfo;
+use crate::money::MoneyFormat;
+use crate::output::{LineStatus, OutputFormat, print_records, ScriptLineRecord};
+use crate::shell::parse_line;
+
+// what a dry run knows about the server, kept up to date line by line so later lines see what earlier ones would do
+struct Inventory {
+    shelves: HashMap<String, u32>,
+    items: HashMap<u32, ItemInfo>
+}
+
+impl Inventory {
+    async fn load(connection: &Connection) -> Result<Inventory, CliError> {
+        let shelves = read_shelves(connection.rpc.clone(), None).await?;
+        let items = list_items(connection, None).await?;
+        Ok(Inventory {
+            shelves: shelves.shelves.into_iter().map(|s| (s.shelf_id, s.shelf_count)).collect(),
+            items: items.into_iter().map(|i| (i.object_id, i)).collect()
+        })
+    }
+
+    fn shelf(&self, shelf_id: &str) -> Result<(), CliError> {
+        if self.shelves.contains_key(shelf_id) {
+            Ok(())
+        } else {
+            Err(CliError::NotFound(format!("shelf {}", shelf_id)))
+        }
+    }
+
+    fn item(&self, item_id: u32) -> Result<&ItemInfo, CliError> {
+        self.items.get(&item_id).ok_or_else(|| CliError::NotFound(format!("item {}", item_id)))
+    }
+
+    fn check(&mut self, command: &Command) -> Result<String, CliError> {
+        match command {
+            Command::Shelves(ShelfCommand::List) => Ok(format!("{} shelves", self.shelves.len())),
+            Command::Shelves(ShelfCommand::Create { shelf_id, slots }) => {
+                check_new_shelf(shelf_id, *slots)?;
+                if self.shelves.contains_key(shelf_id) {
+                    return Err(CliError::Validation(format!("shelf {} already exists", shelf_id)));
+                }
+                self.shelves.insert(shelf_id.clone(), *slots);
+                Ok(format!("would create shelf {}", shelf_id))
+            }
+            Command::Shelves(ShelfCommand::Slots { shelf_id }) => {
+                self.shelf(shelf_id)?;
+                Ok(format!("{} slots", self.shelves[shelf_id]))
+            }
+            Command::Items(ItemCommand::List { shelf }) => {
+                if let Some(shelf_id) = shelf {
+                    self.shelf(shelf_id)?;
+                }
+                let count = self.items.values().filter(|i| shelf.as_ref().map(|s| s == &i.shelf_id).unwrap_or(true)).count();
+                Ok(format!("{} items", count))
+            }
+            Command::Items(ItemCommand::Get { item_id }) => {
+                self.item(*item_id)?;
+                Ok(format!("item {} exists", item_id))
+            }
+            _ => Err(not_scriptable())
+        }
+    }
+}
+
+fn not_scriptable() -> CliError {
+    CliError::Validation("only shelf and item commands can be scripted".to_owned())
+}
+
+fn read_script(file: Option<&Path>) -> Result<String, CliError> {
+    let read = match file {
+        Some(path) if path != Path::new("-") => fs::read_to_string(path),
+        _ => {
+            let mut script = String::new();
+            io::stdin().read_to_string(&mut script).map(|_| script)
+        }
+    };
+    read.map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => CliError::NotFound(format!("script {}", file.map(|p| p.display().to_string()).unwrap_or_default())),
+        _ => CliError::Validation(format!("could not read the script: {}", e))
+    })
+}
+
+// blank lines and lines starting with # are ignored, every other line gets a row in the report
+fn script_lines(script: &str) -> impl Iterator<Item = (usize, &str)> {
+    script.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn parse_script_line(line: &str) -> Result<Command, CliError> {
+    // clap's usage text doesn't fit in a report row, its first line says what is wrong
+    parse_line(line).map_err(|e| CliError::Validation(e.lines().next().unwrap_or_default().trim_start_matches("error: ").to_owned()))
+}
+
+struct Report {
+    rows: Vec<ScriptLineRecord>,
+    first_error: Option<CliError>,
+    continue_on_error: bool,
+    success: LineStatus
+}
+
+impl Report {
+    fn new(dry_run: bool, continue_on_error: bool) -> Report {
+        Report {
+            rows: Vec::new(),
+            first_error: None,
+            continue_on_error,
+            success: if dry_run { LineStatus::Valid } else { LineStatus::Ok }
+        }
+    }
+
+    // after the first failure the remaining lines are only listed, unless the script asked to carry on
+    fn stopped(&self) -> bool {
+        self.first_error.is_some() && !self.continue_on_error
+    }
+
+    fn skip(&mut self, line: usize, command: &str) {
+        self.rows.push(ScriptLineRecord { line, command: command.to_owned(), status: LineStatus::Skipped, message: String::new() });
+    }
+
+    fn add(&mut self, line: usize, command: &str, result: Result<String, CliError>) {
+        let (status, message) = match result {
+            Ok(message) => (self.success, message),
+            Err(e) => {
+                let message = e.to_string();
+                self.first_error.get_or_insert(e);
+                (LineStatus::Failed, message)
+            }
+        };
+        self.rows.push(ScriptLineRecord { line, command: command.to_owned(), status, message });
+    }
+}
+
+// the dry run half of run_script, kept free of the connection so it can be checked on its own
+fn check_script(script: &str, inventory: &mut Inventory, continue_on_error: bool) -> Report {
+    let mut report = Report::new(true, continue_on_error);
+    for (number, line) in script_lines(script) {
+        if report.stopped() {
+            report.skip(number, line);
+            continue;
+        }
+        report.add(number, line, parse_script_line(line).and_then(|command| inventory.check(&command)));
+    }
+    report
+}
+
+pub(crate) async fn run_script(connection: &Connection, file: Option<&Path>, dry_run: bool, continue_on_error: bool, output: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+    let script = read_script(file)?;
+
+    let report = if dry_run {
+        check_script(&script, &mut Inventory::load(connection).await?, continue_on_error)
+    } else {
+        let mut report = Report::new(false, continue_on_error);
+        for (number, line) in script_lines(&script) {
+            if report.stopped() {
+                report.skip(number, line);
+                continue;
+            }
+            let result = match parse_script_line(line) {
+                Ok(command) if !command.is_scriptable() => Err(not_scriptable()),
+                Ok(command) => execute(connection, &command).await.map(|o| o.summary()),
+                Err(e) => Err(e)
+            };
+            report.add(number, line, result);
+        }
+        report
+    };
+
+    print_records(&report.rows, output, money_format)?;
+    match report.first_error {
+        Some(e) => Err(e),
+        None => Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory() -> Inventory {
+        let soap = ItemInfo { object_id: 1, shelf_id: "A1".to_owned(), description: "Soap".to_owned(), stock: 3, price: 249 };
+        Inventory {
+            shelves: HashMap::from([("A1".to_owned(), 4)]),
+            items: HashMap::from([(1, soap)])
+        }
+    }
+
+    fn check(inventory: &mut Inventory, line: &str) -> Result<String, CliError> {
+        parse_script_line(line).and_then(|command| inventory.check(&command))
+    }
+
+    fn statuses(report: &Report) -> Vec<LineStatus> {
+        report.rows.iter().map(|r| r.status).collect()
+    }
+
+    #[test]
+    fn checks_reads_against_the_inventory() {
+        let mut inventory = inventory();
+        assert_eq!(check(&mut inventory, "shelves list").unwrap(), "1 shelves");
+        assert_eq!(check(&mut inventory, "shelves slots A1").unwrap(), "4 slots");
+        assert_eq!(check(&mut inventory, "items list --shelf A1").unwrap(), "1 items");
+        assert_eq!(check(&mut inventory, "items get 1").unwrap(), "item 1 exists");
+        assert!(matches!(check(&mut inventory, "shelves slots B2"), Err(CliError::NotFound(_))));
+        assert!(matches!(check(&mut inventory, "items list --shelf B2"), Err(CliError::NotFound(_))));
+        assert!(matches!(check(&mut inventory, "items get 9"), Err(CliError::NotFound(_))));
+    }
+
+    #[test]
+    fn only_shelf_and_item_commands_are_scriptable() {
+        let mut inventory = inventory();
+        assert!(matches!(check(&mut inventory, "shell"), Err(CliError::Validation(_))));
+        assert!(matches!(check(&mut inventory, "login"), Err(CliError::Validation(_))));
+        assert!(matches!(check(&mut inventory, "shelves destroy A1"), Err(CliError::Validation(_))));
+    }
+
+    #[test]
+    fn later_lines_see_shelves_earlier_lines_create() {
+        let mut inventory = inventory();
+        assert_eq!(check(&mut inventory, "shelves create B2 --slots 2").unwrap(), "would create shelf B2");
+        assert_eq!(check(&mut inventory, "shelves slots B2").unwrap(), "2 slots");
+        assert_eq!(check(&mut inventory, "shelves list").unwrap(), "2 shelves");
+    }
+
+    #[test]
+    fn duplicate_shelves_are_rejected() {
+        let mut inventory = inventory();
+        assert!(matches!(check(&mut inventory, "shelves create A1"), Err(CliError::Validation(_))));
+
+        check(&mut inventory, "shelves create B2").unwrap();
+        let duplicate = check(&mut inventory, "shelves create B2 --slots 3");
+        assert!(matches!(duplicate, Err(CliError::Validation(ref m)) if m.contains("already exists")));
+        assert_eq!(inventory.shelves["B2"], 1);
+    }
+
+    #[test]
+    fn invalid_shelves_are_rejected() {
+        let mut inventory = inventory();
+        assert!(matches!(check(&mut inventory, "shelves create C3 --slots 0"), Err(CliError::Validation(_))));
+        assert!(matches!(check(&mut inventory, "shelves create ' '"), Err(CliError::Validation(_))));
+        assert!(!inventory.shelves.contains_key("C3"));
+    }
+
+    #[test]
+    fn blank_lines_and_comments_get_no_row() {
+        let report = check_script("# intake\n\n  shelves list  \n", &mut inventory(), false);
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!((report.rows[0].line, report.rows[0].command.as_str()), (3, "shelves list"));
+    }
+
+    #[test]
+    fn stops_at_the_first_error_and_exits_with_its_code() {
+        let script = "shelves list\nitems get 9\nshelves create A1\nshelves list\n";
+        let report = check_script(script, &mut inventory(), false);
+        assert_eq!(statuses(&report), vec![LineStatus::Valid, LineStatus::Failed, LineStatus::Skipped, LineStatus::Skipped]);
+        assert_eq!(report.first_error.map(|e| e.exit_code()), Some(4));
+    }
+
+    #[test]
+    fn continuing_after_errors_keeps_the_first_exit_code() {
+        let script = "items get 9\nshelves create A1\nshelves list\n";
+        let report = check_script(script, &mut inventory(), true);
+        assert_eq!(statuses(&report), vec![LineStatus::Failed, LineStatus::Failed, LineStatus::Valid]);
+        assert_eq!(report.first_error.map(|e| e.exit_code()), Some(4));
+    }
+
+    #[test]
+    fn unparseable_lines_are_invalid_input() {
+        let report = check_script("shelves create\n", &mut inventory(), false);
+        assert_eq!(statuses(&report), vec![LineStatus::Failed]);
+        assert!(!report.rows[0].message.contains('\n'));
+        assert_eq!(report.first_error.map(|e| e.exit_code()), Some(6));
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use directories::ProjectDirs;
+#[cfg(feature = "gui")]
+use iced::{Event, Subscription, subscription, window};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use crate::frontend::{ShelfSort, TabId};
+#[cfg(feature = "gui")]
+use crate::ui_messages::Message;
+
+pub(crate) const SESSION_VERSION: u32 = 1;
+const SESSION_FILE: &str = "session.json";
+pub(crate) const DEFAULT_WINDOW_SIZE: (u32, u32) = (1024, 768);
+pub(crate) const DEFAULT_UI_SCALE: u16 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Session {
+    pub(crate) version: u32,
+    pub(crate) server: String,
+    pub(crate) profile: Option<String>,
+    pub(crate) username: String,
+    pub(crate) tabs: Vec<TabId>,
+    pub(crate) pinned_tabs: Vec<TabId>,
+    pub(crate) active_tab: Option<TabId>,
+    pub(crate) shelf_sort: ShelfSort,
+    pub(crate) item_filter: String,
+    pub(crate) theme: Option<String>,
+    pub(crate) locale: Option<String>,
+    pub(crate) ui_scale: u16,
+    pub(crate) window_size: (u32, u32)
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            version: SESSION_VERSION,
+            server: crate::SERVER_ADDRESS.to_owned(),
+            profile: None,
+            username: String::new(),
+            tabs: Vec::new(),
+            pinned_tabs: Vec::new(),
+            active_tab: None,
+            shelf_sort: ShelfSort::default(),
+            item_filter: String::new(),
+            theme: None,
+            locale: None,
+            ui_scale: DEFAULT_UI_SCALE,
+            window_size: DEFAULT_WINDOW_SIZE
+        }
+    }
+}
+
+pub(crate) fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("ca", "CS4471", "sims-cli")
+}
+
+// XDG_STATE_HOME only exists on Linux, other platforms keep state next to local data
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    project_dirs().map(|d| d.state_dir().unwrap_or_else(|| d.data_local_dir()).to_path_buf())
+}
+
+fn session_path() -> Option<PathBuf> {
+    state_dir().map(|d| d.join(SESSION_FILE))
+}
+
+impl Session {
+    // anything unreadable is set aside and replaced with a fresh session rather than stopping startup
+    pub(crate) fn load() -> Session {
+        match session_path() {
+            Some(path) => Session::load_from(&path),
+            None => Session::default()
+        }
+    }
+
+    fn load_from(path: &Path) -> Session {
+        let contents = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                debug!("No saved session at {:?}: {}", path, e);
+                return Session::default();
+            }
+        };
+
+        match serde_json::from_str::<serde_json::Value>(&contents) {
+            Ok(value) => match value.get("version").and_then(|v| v.as_u64()) {
+                Some(v) if v == SESSION_VERSION as u64 => serde_json::from_value(value).unwrap_or_else(|e| {
+                    warn!("Saved session could not be read: {}", e);
+                    set_aside(path);
+                    Session::default()
+                }),
+                other => {
+                    warn!("Ignoring saved session with unsupported version {:?}", other);
+                    Session::default()
+                }
+            },
+            Err(e) => {
+                warn!("Saved session is corrupt: {}", e);
+                set_aside(path);
+                Session::default()
+            }
+        }
+    }
+
+    pub(crate) fn save(&self) -> io::Result<()> {
+        let path = session_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+        self.save_to(&path)
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // write then rename so a crash mid-save can't leave a truncated file behind
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(temp_path, path)
+    }
+}
+
+fn set_aside(path: &Path) {
+    if let Err(e) = fs::rename(path, path.with_extension("json.corrupt")) {
+        warn!("Could not move corrupt session out of the way: {}", e);
+    }
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn subscription() -> Subscription<Message> {
+    subscription::events_with(|event, _status| match event {
+        Event::Window(window::Event::Resized { width, height }) => Some(Message::WindowResized(width, height)),
+        Event::Window(window::Event::CloseRequested) => Some(Message::CloseRequested),
+        _ => None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a fresh directory per test, the tests run in parallel
+    fn session_file(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sims-session-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(SESSION_FILE)
+    }
+
+    #[test]
+    fn a_saved_session_loads_back() {
+        let path = session_file("round-trip");
+        let session = Session {
+            username: "alice".to_owned(),
+            tabs: vec![TabId::AllShelves, TabId::ShelfView("A1".to_owned())],
+            active_tab: Some(TabId::ShelfView("A1".to_owned())),
+            item_filter: "soap".to_owned(),
+            ui_scale: 125,
+            window_size: (800, 600),
+            ..Session::default()
+        };
+        session.save_to(&path).unwrap();
+
+        let loaded = Session::load_from(&path);
+        assert_eq!(loaded.username, "alice");
+        assert_eq!(loaded.tabs, session.tabs);
+        assert_eq!(loaded.active_tab, session.active_tab);
+        assert_eq!(loaded.item_filter, "soap");
+        assert_eq!((loaded.ui_scale, loaded.window_size), (125, (800, 600)));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn missing_fields_take_their_defaults() {
+        let path = session_file("missing-fields");
+        fs::write(&path, format!(r#"{{"version": {}, "username": "alice"}}"#, SESSION_VERSION)).unwrap();
+
+        let loaded = Session::load_from(&path);
+        assert_eq!(loaded.username, "alice");
+        assert_eq!(loaded.window_size, DEFAULT_WINDOW_SIZE);
+    }
+
+    #[test]
+    fn a_corrupt_file_is_set_aside() {
+        let path = session_file("corrupt");
+        fs::write(&path, "{ not json").unwrap();
+
+        assert_eq!(Session::load_from(&path).username, "");
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(path.with_extension("json.corrupt")).unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn a_file_with_unreadable_fields_is_set_aside() {
+        let path = session_file("bad-fields");
+        fs::write(&path, format!(r#"{{"version": {}, "tabs": "A1"}}"#, SESSION_VERSION)).unwrap();
+
+        assert!(Session::load_from(&path).tabs.is_empty());
+        assert!(!path.exists());
+        assert!(path.with_extension("json.corrupt").exists());
+    }
+
+    #[test]
+    fn another_version_is_dropped() {
+        let path = session_file("version");
+        fs::write(&path, format!(r#"{{"version": {}, "username": "alice"}}"#, SESSION_VERSION + 1)).unwrap();
+
+        assert_eq!(Session::load_from(&path).username, "");
+        assert!(!path.with_extension("json.corrupt").exists());
+    }
+
+    #[test]
+    fn no_file_gives_a_default_session() {
+        let path = session_file("missing");
+        assert_eq!(Session::load_from(&path).version, SESSION_VERSION);
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::PathBuf;
+use clap::{CommandFactory, Parser};
+use log::warn;
+use rustyline::{Context, Editor, Helper};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use crate::cli::{CliError, Command, Connection, execute, list_items};
+use crate::frontend::read_shelves;
+use crate::money::MoneyFormat;
+use crate::output::OutputFormat;
+use crate::session::state_dir;
+
+const HISTORY_FILE: &str = "shell_history";
+const EXIT_COMMANDS: [&str; 2] = ["exit", "quit"];
+
+// a shell or script line is a headless command without the program name or global options
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+struct ShellLine {
+    #[command(subcommand)]
+    command: Command
+}
+
+// ids offered for completion, refreshed from the server whenever a command changes them
+#[derive(Default)]
+struct ShellHelper {
+    shelf_ids: Vec<String>,
+    item_ids: Vec<String>
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..pos];
+        let previous_words = line[..start].split_whitespace().collect::<Vec<&str>>();
+
+        // walk down the clap definition so completion can never drift from what the parser accepts
+        let mut command = ShellLine::command();
+        for w in &previous_words {
+            if let Some(subcommand) = command.find_subcommand(w) {
+                command = subcommand.clone();
+            }
+        }
+
+        let candidates = if previous_words.last() == Some(&"--shelf") {
+            self.shelf_ids.clone()
+        } else if word.starts_with('-') {
+            command.get_arguments().filter_map(|a| a.get_long()).map(|l| format!("--{}", l)).collect()
+        } else if command.has_subcommands() {
+            let mut names = command.get_subcommands().map(|c| c.get_name().to_owned()).collect::<Vec<String>>();
+            if previous_words.is_empty() {
+                names.extend(EXIT_COMMANDS.iter().map(|c| c.to_string()));
+            }
+            names
+        } else {
+            match command.get_name() {
+                "slots" => self.shelf_ids.clone(),
+                "get" => self.item_ids.clone(),
+                _ => Vec::new()
+            }
+        };
+
+        Ok((start, candidates.into_iter()
+            .filter(|c| c.starts_with(word))
+            .map(|c| Pair { display: c.clone(), replacement: c })
+            .collect()))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+pub(crate) fn parse_line(line: &str) -> Result<Command, String> {
+    let words = split_words(line)?;
+    ShellLine::try_parse_from(words).map(|l| l.command).map_err(|e| e.to_string())
+}
+
+fn history_path() -> Option<PathBuf> {
+    state_dir().map(|d| d.join(HISTORY_FILE))
+}
+
+// splits on whitespace, single or double quotes keep names with spaces together
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if let Some(q) = quote {
+        return Err(format!("unclosed {} quote", q));
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+async fn refresh_completions(editor: &mut Editor<ShellHelper>, connection: &Connection) {
+    let shelves = read_shelves(connection.rpc.clone(), None).await;
+    let items = list_items(connection, None).await;
+
+    if let Some(helper) = editor.helper_mut() {
+        match shelves {
+            Ok(shelves) => helper.shelf_ids = shelves.shelves.into_iter().map(|s| s.shelf_id).collect(),
+            Err(e) => warn!("Could not load shelves for completion: {:?}", e)
+        }
+        match items {
+            Ok(items) => helper.item_ids = items.into_iter().map(|i| i.object_id.to_string()).collect(),
+            Err(e) => warn!("Could not load items for completion: {}", e)
+        }
+    }
+}
+
+pub(crate) async fn run_shell(connection: &Connection, output: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+    let mut editor = Editor::<ShellHelper>::new().map_err(|e| CliError::Output(e.to_string()))?;
+    editor.set_helper(Some(ShellHelper::default()));
+    refresh_completions(&mut editor, connection).await;
+
+    let history = history_path();
+    if let Some(path) = &history {
+        // there is no history on the first run
+        let _ = editor.load_history(path);
+    }
+
+    eprintln!("Signed in as {}. Type help for commands, exit to leave.", connection.username);
+    let prompt = format!("{}> ", connection.username);
+    loop {
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            // Ctrl+C only drops the current line, Ctrl+D leaves
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(CliError::Output(e.to_string()))
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line);
+        if EXIT_COMMANDS.contains(&line) {
+            break;
+        }
+
+        let command = match parse_line(line) {
+            Ok(command) => command,
+            Err(e) => {
+                eprintln!("{}", e.trim_end());
+                continue;
+            }
+        };
+
+        if let Err(e) = execute(connection, &command).await.and_then(|o| o.print(output, money_format)) {
+            eprintln!("{}", e);
+        }
+        if command.is_mutation() {
+            refresh_completions(&mut editor, connection).await;
+        }
+    }
+
+    if let Some(path) = &history {
+        let saved = match path.parent() {
+            Some(dir) => fs::create_dir_all(dir).map_err(|e| e.to_string()),
+            None => Ok(())
+        }.and_then(|_| editor.save_history(path).map_err(|e| e.to_string()));
+        if let Err(e) = saved {
+            warn!("Could not save shell history to {:?}: {}", path, e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_any_whitespace() {
+        assert_eq!(split_words("  items adjust\t12 --by -3  "), Ok(vec!["items", "adjust", "12", "--by", "-3"].into_iter().map(String::from).collect()));
+        assert_eq!(split_words(""), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn quotes_keep_spaces_and_can_be_empty() {
+        assert_eq!(
+            split_words(r#"items create --name "Paper towels" --shelf 'A 1' --price """#),
+            Ok(vec!["items", "create", "--name", "Paper towels", "--shelf", "A 1", "--price", ""].into_iter().map(String::from).collect())
+        );
+        assert_eq!(split_words(r#"pre"fix"'d'"#), Ok(vec!["prefixd".to_owned()]));
+        assert_eq!(split_words(r#"say "it's""#), Ok(vec!["say".to_owned(), "it's".to_owned()]));
+    }
+
+    #[test]
+    fn an_unclosed_quote_is_an_error() {
+        assert_eq!(split_words(r#"items create --name "Paper"#), Err("unclosed \" quote".to_owned()));
+    }
+}
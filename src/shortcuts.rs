@@ -0,0 +1,70 @@
+use iced::{Event, Subscription, subscription};
+use iced::keyboard::{self, KeyCode, Modifiers};
+use iced::mouse;
+use crate::ui_messages::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shortcut {
+    NewObject,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    Refresh,
+    FocusSearch,
+    TogglePalette,
+    Back,
+    Forward,
+    Up,
+    Down,
+    Escape,
+    FocusNext,
+    FocusPrevious,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset
+}
+
+pub(crate) fn subscription() -> Subscription<Message> {
+    // text inputs capture every key they see, so the capture status can't be used to filter shortcuts
+    subscription::events_with(|event, _status| match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }) => {
+            shortcut_for(key_code, modifiers).map(Message::Shortcut)
+        }
+        // the side buttons are reported as 8/9 on X11, 1/2 on Windows and 3/4 on macOS
+        Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Other(b))) => match b {
+            1 | 3 | 8 => Some(Message::Shortcut(Shortcut::Back)),
+            2 | 4 | 9 => Some(Message::Shortcut(Shortcut::Forward)),
+            _ => None
+        },
+        _ => None
+    })
+}
+
+fn shortcut_for(key_code: KeyCode, modifiers: Modifiers) -> Option<Shortcut> {
+    if modifiers.alt() {
+        return match key_code {
+            KeyCode::Left => Some(Shortcut::Back),
+            KeyCode::Right => Some(Shortcut::Forward),
+            _ => None
+        };
+    }
+
+    match (key_code, modifiers.command(), modifiers.shift()) {
+        (KeyCode::K, true, _) => Some(Shortcut::TogglePalette),
+        (KeyCode::N, true, _) => Some(Shortcut::NewObject),
+        (KeyCode::W, true, _) => Some(Shortcut::CloseTab),
+        (KeyCode::Tab, true, false) | (KeyCode::PageDown, true, _) => Some(Shortcut::NextTab),
+        (KeyCode::Tab, true, true) | (KeyCode::PageUp, true, _) => Some(Shortcut::PreviousTab),
+        (KeyCode::R, true, _) | (KeyCode::F5, _, _) => Some(Shortcut::Refresh),
+        (KeyCode::F, true, _) => Some(Shortcut::FocusSearch),
+        (KeyCode::Equals | KeyCode::Plus | KeyCode::NumpadAdd, true, _) => Some(Shortcut::ZoomIn),
+        (KeyCode::Minus | KeyCode::NumpadSubtract, true, _) => Some(Shortcut::ZoomOut),
+        (KeyCode::Key0 | KeyCode::Numpad0, true, _) => Some(Shortcut::ZoomReset),
+        (KeyCode::Tab, false, false) => Some(Shortcut::FocusNext),
+        (KeyCode::Tab, false, true) => Some(Shortcut::FocusPrevious),
+        (KeyCode::Up, false, _) => Some(Shortcut::Up),
+        (KeyCode::Down, false, _) => Some(Shortcut::Down),
+        (KeyCode::Escape, _, _) => Some(Shortcut::Escape),
+        _ => None
+    }
+}
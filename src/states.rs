@@ -1,8 +1,13 @@
+use crate::suggestions::Suggestion;
+
 #[derive(Debug)]
 pub(crate) enum SimsClientState {
     Unauthenticated{password: String, error_message: Option<String>},
+    Registering{password: String, confirm_password: String, error_message: Option<String>},
     Authenticating,
     InventoryView,
     AwaitDatabaseConfirmation,
-    DisplaySuggestions{suggestion: String}
+    DisplaySuggestions{suggestions: Vec<Suggestion>},
+    Settings,
+    Account
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
-use iced::{Theme, theme};
-use iced::widget::{button};
+use iced::{Background, Theme, theme};
+use iced::widget::{button, container};
 use iced::widget::button::Appearance;
 use num_traits::float::Float;
 
@@ -29,4 +29,89 @@ impl button::StyleSheet for Fab {
             ..style.pressed(&theme::Button::Primary)
         }
     }
-}
\ No newline at end of file
+}
+
+// the active tab takes the primary colour, the rest sit back as secondary buttons
+pub struct Tab {
+    pub active: bool
+}
+
+impl Tab {
+    fn base(&self) -> theme::Button {
+        if self.active { theme::Button::Primary } else { theme::Button::Secondary }
+    }
+}
+
+impl button::StyleSheet for Tab {
+    type Style = Theme;
+
+    fn active(&self, style: &Self::Style) -> Appearance {
+        Appearance{
+            border_radius: 4.0,
+            ..style.active(&self.base())
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        Appearance{
+            border_radius: 4.0,
+            ..style.hovered(&self.base())
+        }
+    }
+
+    fn pressed(&self, style: &Self::Style) -> Appearance {
+        Appearance{
+            border_radius: 4.0,
+            ..style.pressed(&self.base())
+        }
+    }
+}
+
+// container styles are plain functions so they can be handed to theme::Container::Custom
+
+pub fn table_header(theme: &Theme) -> container::Appearance {
+    let palette = theme.extended_palette();
+    container::Appearance {
+        text_color: Some(palette.background.strong.text),
+        background: Some(Background::Color(palette.background.strong.color)),
+        border_radius: 2.0,
+        ..container::Appearance::default()
+    }
+}
+
+pub fn table_row(_theme: &Theme) -> container::Appearance {
+    container::Appearance::default()
+}
+
+pub fn table_row_alternate(theme: &Theme) -> container::Appearance {
+    let palette = theme.extended_palette();
+    container::Appearance {
+        background: Some(Background::Color(palette.background.weak.color)),
+        ..container::Appearance::default()
+    }
+}
+
+pub fn table_row_style(index: usize) -> theme::Container {
+    theme::Container::Custom(if index % 2 == 0 { table_row } else { table_row_alternate })
+}
+
+pub fn alert(theme: &Theme) -> container::Appearance {
+    let palette = theme.extended_palette();
+    container::Appearance {
+        text_color: Some(palette.danger.base.text),
+        background: Some(Background::Color(palette.danger.base.color)),
+        border_radius: 4.0,
+        ..container::Appearance::default()
+    }
+}
+
+pub fn modal(theme: &Theme) -> container::Appearance {
+    let palette = theme.extended_palette();
+    container::Appearance {
+        text_color: Some(palette.background.base.text),
+        background: Some(Background::Color(palette.background.base.color)),
+        border_radius: 6.0,
+        border_width: 1.0,
+        border_color: palette.background.strong.color
+    }
+}
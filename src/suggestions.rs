@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::frontend::shelf_slots;
+use crate::i18n::{format_number, tr_args};
+use crate::frontend::sims_ims_frontend::{ItemInfo, ShelfInfo};
+
+pub(crate) const LOW_STOCK_THRESHOLD: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Suggestion {
+    Restock{item: ItemInfo, target_stock: u32},
+    Consolidate{shelf_id: String, slots: Vec<u32>}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Thresholds {
+    pub(crate) low_stock: u32,
+    pub(crate) slot_capacity: u32
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            low_stock: LOW_STOCK_THRESHOLD,
+            slot_capacity: crate::frontend::DEFAULT_SLOT_CAPACITY
+        }
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suggestion::Restock { item, target_stock } => write!(f, "{}", tr_args("suggest-restock", &[
+                ("item", item.object_id.into()),
+                ("shelf", item.shelf_id.as_str().into()),
+                ("target", format_number(*target_stock as u64).into())
+            ])),
+            Suggestion::Consolidate { shelf_id, slots } => write!(f, "{}", tr_args("suggest-consolidate", &[
+                ("slots", slots.iter().map(|s| s.to_string()).collect::<Vec<String>>().join(", ").into()),
+                ("shelf", shelf_id.as_str().into())
+            ]))
+        }
+    }
+}
+
+pub(crate) fn generate_suggestions(shelves: &[ShelfInfo], all_items: &HashMap<String, Vec<ItemInfo>>, thresholds: Thresholds) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+
+    for shelf in shelves {
+        let items = all_items.get(&shelf.shelf_id).map(|i| i.as_slice()).unwrap_or(&[]);
+
+        for item in items {
+            if item.stock < thresholds.low_stock {
+                suggestions.push(Suggestion::Restock {
+                    item: item.clone(),
+                    target_stock: thresholds.slot_capacity.max(thresholds.low_stock)
+                });
+            }
+        }
+
+        // an empty slot is left alone, only partially filled ones are worth merging.
+        // slots are estimated from the item list, the server doesn't report them
+        let half_empty = shelf_slots(shelf, items, thresholds.slot_capacity)
+            .into_iter()
+            .filter(|s| s.item_count > 0 && s.item_count as u64 * 2 <= s.capacity as u64)
+            .map(|s| s.slot_num)
+            .collect::<Vec<u32>>();
+
+        if half_empty.len() >= 2 {
+            suggestions.push(Suggestion::Consolidate {
+                shelf_id: shelf.shelf_id.clone(),
+                slots: half_empty
+            });
+        }
+    }
+
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLDS: Thresholds = Thresholds { low_stock: 10, slot_capacity: 30 };
+
+    fn shelf(shelf_id: &str, shelf_count: u32) -> ShelfInfo {
+        ShelfInfo { shelf_id: shelf_id.to_owned(), shelf_count }
+    }
+
+    fn items(shelf_id: &str, stocks: &[u32]) -> HashMap<String, Vec<ItemInfo>> {
+        let items = stocks.iter().enumerate().map(|(i, stock)| ItemInfo {
+            object_id: i as u32 + 1,
+            shelf_id: shelf_id.to_owned(),
+            description: format!("item {}", i + 1),
+            stock: *stock,
+            price: 100
+        }).collect();
+        HashMap::from([(shelf_id.to_owned(), items)])
+    }
+
+    #[test]
+    fn restocks_items_below_the_threshold_to_a_full_slot() {
+        let all_items = items("A1", &[3, 10, 30]);
+        let suggestions = generate_suggestions(&[shelf("A1", 3)], &all_items, THRESHOLDS);
+        assert_eq!(suggestions, vec![Suggestion::Restock { item: all_items["A1"][0].clone(), target_stock: 30 }]);
+    }
+
+    #[test]
+    fn restock_target_is_never_below_the_threshold() {
+        let all_items = items("A1", &[3]);
+        let thresholds = Thresholds { low_stock: 50, slot_capacity: 30 };
+        let suggestions = generate_suggestions(&[shelf("A1", 1)], &all_items, thresholds);
+        assert_eq!(suggestions, vec![Suggestion::Restock { item: all_items["A1"][0].clone(), target_stock: 50 }]);
+    }
+
+    #[test]
+    fn consolidates_two_or_more_half_empty_slots() {
+        let all_items = items("A1", &[12, 15, 16, 40]);
+        let suggestions = generate_suggestions(&[shelf("A1", 4)], &all_items, THRESHOLDS);
+        assert_eq!(suggestions, vec![Suggestion::Consolidate { shelf_id: "A1".to_owned(), slots: vec![0, 1] }]);
+    }
+
+    #[test]
+    fn empty_slots_and_a_single_half_empty_one_are_left_alone() {
+        let all_items = items("A1", &[12, 0, 30]);
+        let suggestions = generate_suggestions(&[shelf("A1", 4)], &all_items, THRESHOLDS);
+        assert!(suggestions.iter().all(|s| matches!(s, Suggestion::Restock { .. })));
+    }
+
+    #[test]
+    fn items_past_the_last_slot_are_not_counted() {
+        let all_items = items("A1", &[12, 15]);
+        let suggestions = generate_suggestions(&[shelf("A1", 1)], &all_items, THRESHOLDS);
+        assert!(suggestions.is_empty());
+    }
+
+    #[test]
+    fn shelves_without_items_suggest_nothing() {
+        assert!(generate_suggestions(&[shelf("A1", 4)], &HashMap::new(), THRESHOLDS).is_empty());
+    }
+}
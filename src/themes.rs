@@ -0,0 +1,171 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+use iced::{Color, Theme};
+use iced::theme::Palette;
+use log::warn;
+use serde::Deserialize;
+use crate::i18n::tr;
+use crate::session::{DEFAULT_UI_SCALE, project_dirs};
+
+const THEMES_FILE: &str = "themes.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ThemeChoice {
+    Light,
+    Dark,
+    System,
+    Custom(String)
+}
+
+impl ThemeChoice {
+    pub(crate) fn from_name(name: &str) -> ThemeChoice {
+        match name.to_lowercase().as_str() {
+            "light" => ThemeChoice::Light,
+            "dark" | "" => ThemeChoice::Dark,
+            "system" => ThemeChoice::System,
+            _ => ThemeChoice::Custom(name.to_owned())
+        }
+    }
+
+    pub(crate) fn name(&self) -> String {
+        match self {
+            ThemeChoice::Light => "light".to_owned(),
+            ThemeChoice::Dark => "dark".to_owned(),
+            ThemeChoice::System => "system".to_owned(),
+            ThemeChoice::Custom(name) => name.clone()
+        }
+    }
+}
+
+impl Display for ThemeChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeChoice::Light => write!(f, "{}", tr("theme-light")),
+            ThemeChoice::Dark => write!(f, "{}", tr("theme-dark")),
+            ThemeChoice::System => write!(f, "{}", tr("theme-system")),
+            ThemeChoice::Custom(name) => write!(f, "{}", name)
+        }
+    }
+}
+
+pub(crate) const UI_SCALES: [u16; 8] = [75, 90, 100, 110, 125, 150, 175, 200];
+
+// a percentage so it can be compared and listed, iced wants the factor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UiScale(pub(crate) u16);
+
+impl UiScale {
+    pub(crate) fn factor(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    pub(crate) fn step(&self, up: bool) -> UiScale {
+        let next = if up {
+            UI_SCALES.iter().find(|s| **s > self.0)
+        } else {
+            UI_SCALES.iter().rev().find(|s| **s < self.0)
+        };
+        UiScale(*next.unwrap_or(&self.0))
+    }
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        UiScale(DEFAULT_UI_SCALE)
+    }
+}
+
+impl Display for UiScale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct CustomPalette {
+    pub(crate) name: String,
+    background: String,
+    text: String,
+    primary: String,
+    success: String,
+    danger: String
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThemesFile {
+    #[serde(default, rename = "palette")]
+    palettes: Vec<CustomPalette>
+}
+
+impl CustomPalette {
+    fn palette(&self) -> Option<Palette> {
+        Some(Palette {
+            background: parse_hex(&self.background)?,
+            text: parse_hex(&self.text)?,
+            primary: parse_hex(&self.primary)?,
+            success: parse_hex(&self.success)?,
+            danger: parse_hex(&self.danger)?
+        })
+    }
+}
+
+pub(crate) fn themes_path() -> Option<PathBuf> {
+    project_dirs().map(|d| d.config_dir().join(THEMES_FILE))
+}
+
+pub(crate) fn load_palettes() -> Vec<CustomPalette> {
+    let path = match themes_path() {
+        Some(p) => p,
+        None => return Vec::new()
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return Vec::new()
+    };
+
+    match toml::from_str::<ThemesFile>(&contents) {
+        Ok(file) => file.palettes.into_iter().filter(|p| {
+            let valid = p.palette().is_some();
+            if !valid {
+                warn!("Palette {} in {:?} has an invalid colour and was skipped", p.name, path);
+            }
+            valid
+        }).collect(),
+        Err(e) => {
+            warn!("Could not read palettes from {:?}: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+pub(crate) fn system_prefers_dark() -> bool {
+    !matches!(dark_light::detect(), dark_light::Mode::Light)
+}
+
+pub(crate) fn resolve_theme(choice: &ThemeChoice, palettes: &[CustomPalette], system_dark: bool) -> Theme {
+    match choice {
+        ThemeChoice::Light => Theme::Light,
+        ThemeChoice::Dark => Theme::Dark,
+        ThemeChoice::System => if system_dark { Theme::Dark } else { Theme::Light },
+        ThemeChoice::Custom(name) => palettes
+            .iter()
+            .find(|p| p.name == *name)
+            .and_then(|p| p.palette())
+            .map(Theme::custom)
+            .unwrap_or(Theme::Dark)
+    }
+}
+
+// accepts #rrggbb or #rrggbbaa
+fn parse_hex(value: &str) -> Option<Color> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 && hex.len() != 8 {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? as f32 / 255.0 } else { 1.0 };
+    Some(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
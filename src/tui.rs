@@ -0,0 +1,381 @@
+use std::io::{self, Stdout};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Span, Spans};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{Frame, Terminal};
+use crate::cli::{CliError, Connection, list_items};
+use crate::frontend::{create_shelf, DEFAULT_SLOT_CAPACITY, read_shelves, shelf_slots, slot_fill, TabId};
+use crate::frontend::sims_ims_frontend::{ItemInfo, ShelfInfo};
+use crate::i18n::{format_number, LocaleChoice, set_locale, tr, tr_args};
+use crate::money::MoneyFormat;
+use crate::session::Session;
+
+const SHELF_COLUMNS: [Constraint; 4] = [Constraint::Percentage(40), Constraint::Percentage(20), Constraint::Percentage(20), Constraint::Percentage(20)];
+const ITEM_COLUMNS: [Constraint; 5] = [Constraint::Length(8), Constraint::Percentage(40), Constraint::Percentage(20), Constraint::Length(10), Constraint::Length(14)];
+
+struct Field {
+    label: String,
+    value: String
+}
+
+// the terminal counterpart of the GUI's new shelf form, every field is free text until it is submitted
+struct Form {
+    fields: Vec<Field>,
+    focus: usize,
+    error: Option<String>
+}
+
+impl Form {
+    fn new(fields: Vec<(String, String)>) -> Form {
+        Form {
+            fields: fields.into_iter().map(|(label, value)| Field { label, value }).collect(),
+            focus: 0,
+            error: None
+        }
+    }
+
+    fn title(&self) -> String {
+        tr("new-shelf")
+    }
+
+    fn value(&self, index: usize) -> &str {
+        self.fields[index].value.trim()
+    }
+}
+
+// restores the terminal even when drawing panics, otherwise the shell is left in raw mode
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+    }
+}
+
+struct TuiApp<'a> {
+    connection: &'a Connection,
+    money_format: &'a MoneyFormat,
+    tabs: Vec<TabId>,
+    active: usize,
+    shelves: Vec<ShelfInfo>,
+    items: Vec<ItemInfo>,
+    table: TableState,
+    form: Option<Form>,
+    status: Option<String>,
+    quit: bool
+}
+
+impl<'a> TuiApp<'a> {
+    fn new(connection: &'a Connection, money_format: &'a MoneyFormat) -> TuiApp<'a> {
+        TuiApp {
+            connection,
+            money_format,
+            tabs: vec![TabId::AllShelves, TabId::AllItems],
+            active: 0,
+            shelves: Vec::new(),
+            items: Vec::new(),
+            table: TableState::default(),
+            form: None,
+            status: None,
+            quit: false
+        }
+    }
+
+    fn active_tab(&self) -> &TabId {
+        &self.tabs[self.active]
+    }
+
+    // shelf tabs show the matching slice of the item list, so a refresh is two calls however many tabs are open
+    fn visible_items(&self) -> Vec<&ItemInfo> {
+        match self.active_tab() {
+            TabId::AllItems => self.items.iter().collect(),
+            TabId::ShelfView(shelf_id) => self.items.iter().filter(|i| &i.shelf_id == shelf_id).collect(),
+            _ => Vec::new()
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        match self.active_tab() {
+            TabId::AllShelves => self.shelves.len(),
+            _ => self.visible_items().len()
+        }
+    }
+
+    async fn reload(&mut self) {
+        let loaded = async {
+            let shelves = read_shelves(self.connection.rpc.clone(), None).await?;
+            let items = list_items(self.connection, None).await?;
+            Ok::<_, CliError>((shelves.shelves, items))
+        }.await;
+
+        match loaded {
+            Ok((shelves, items)) => {
+                self.shelves = shelves;
+                self.items = items;
+                // tabs of shelves that are gone are closed like the GUI does
+                let shelves = &self.shelves;
+                self.tabs.retain(|t| match t {
+                    TabId::ShelfView(shelf_id) => shelves.iter().any(|s| &s.shelf_id == shelf_id),
+                    _ => true
+                });
+                self.active = self.active.min(self.tabs.len() - 1);
+                self.clamp_selection();
+            }
+            Err(e) => self.status = Some(e.to_string())
+        }
+    }
+
+    fn clamp_selection(&mut self) {
+        let rows = self.row_count();
+        self.table.select(match self.table.selected() {
+            _ if rows == 0 => None,
+            Some(i) => Some(i.min(rows - 1)),
+            None => Some(0)
+        });
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        self.active = index % self.tabs.len();
+        self.table.select(None);
+        self.clamp_selection();
+    }
+
+    fn move_selection(&mut self, step: isize) {
+        let rows = self.row_count();
+        if rows == 0 {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        self.table.select(Some((current + step).clamp(0, rows as isize - 1) as usize));
+    }
+
+    fn close_tab(&mut self) {
+        if let TabId::ShelfView(_) = self.active_tab() {
+            self.tabs.remove(self.active);
+            self.select_tab(self.active.min(self.tabs.len() - 1));
+        }
+    }
+
+    fn open_selected(&mut self) {
+        match self.active_tab() {
+            TabId::AllShelves => {
+                let shelf_id = match self.table.selected().and_then(|i| self.shelves.get(i)) {
+                    Some(shelf) => shelf.shelf_id.clone(),
+                    None => return
+                };
+                let tab = TabId::ShelfView(shelf_id);
+                let index = match self.tabs.iter().position(|t| t == &tab) {
+                    Some(index) => index,
+                    None => {
+                        self.tabs.push(tab);
+                        self.tabs.len() - 1
+                    }
+                };
+                self.select_tab(index);
+            }
+            // items can't be changed over the protocol, so there is nothing to open
+            _ => {}
+        }
+    }
+
+    fn start_new(&mut self) {
+        self.form = Some(Form::new(vec![
+            (tr("field-shelf"), String::new()),
+            (tr("field-slots"), "1".to_owned())
+        ]));
+    }
+
+    async fn submit_form(&mut self) {
+        let mut form = match self.form.take() {
+            Some(form) => form,
+            None => return
+        };
+        let connection = self.connection;
+
+        let shelf_id = form.value(0).to_owned();
+        let result = match form.value(1).parse::<u32>() {
+            _ if shelf_id.is_empty() => Err(tr("error-shelf-empty")),
+            Err(_) => Err(tr("error-slots-natural")),
+            Ok(0) => Err(tr("error-min-slots")),
+            Ok(slots) => create_shelf(connection.rpc.clone(), shelf_id, slots)
+                .await
+                .map_err(|e| CliError::from(e).to_string())
+        };
+
+        match result {
+            Ok(()) => {
+                self.status = Some(tr("tui-saved"));
+                self.reload().await;
+            }
+            Err(e) => {
+                form.error = Some(e);
+                self.form = Some(form);
+            }
+        }
+    }
+
+    async fn handle_form_key(&mut self, key: KeyEvent) {
+        let form = match self.form.as_mut() {
+            Some(form) => form,
+            None => return
+        };
+        match key.code {
+            KeyCode::Esc => self.form = None,
+            KeyCode::Enter => self.submit_form().await,
+            KeyCode::Tab | KeyCode::Down => form.focus = (form.focus + 1) % form.fields.len(),
+            KeyCode::BackTab | KeyCode::Up => form.focus = (form.focus + form.fields.len() - 1) % form.fields.len(),
+            KeyCode::Backspace => {
+                form.fields[form.focus].value.pop();
+            }
+            KeyCode::Char(c) => form.fields[form.focus].value.push(c),
+            _ => {}
+        }
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        if self.form.is_some() {
+            self.handle_form_key(key).await;
+            return;
+        }
+
+        self.status = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => self.select_tab(self.active + 1),
+            KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => self.select_tab(self.active + self.tabs.len() - 1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Enter => self.open_selected(),
+            KeyCode::Char('n') => self.start_new(),
+            KeyCode::Char('w') => self.close_tab(),
+            KeyCode::Char('r') | KeyCode::F(5) => {
+                self.reload().await;
+                self.status.get_or_insert_with(|| tr("tui-refreshed"));
+            }
+            _ => {}
+        }
+    }
+
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+
+        let titles = self.tabs.iter().map(|t| Spans::from(t.to_string())).collect();
+        let tabs = Tabs::new(titles)
+            .block(Block::default().borders(Borders::ALL).title(tr("app-title")))
+            .select(self.active)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+        f.render_widget(tabs, chunks[0]);
+
+        let header_style = Style::default().add_modifier(Modifier::BOLD);
+        let highlight = Style::default().add_modifier(Modifier::REVERSED);
+        let table = match self.active_tab() {
+            TabId::AllShelves => Table::new(self.shelves.iter().map(|s| {
+                let items = self.items.iter().filter(|i| i.shelf_id == s.shelf_id).cloned().collect::<Vec<ItemInfo>>();
+                let fill = slot_fill(&shelf_slots(s, &items, DEFAULT_SLOT_CAPACITY));
+                Row::new(vec![
+                    Cell::from(s.shelf_id.clone()),
+                    Cell::from(format_number(s.shelf_count as u64)),
+                    Cell::from(format_number(items.len() as u64)),
+                    Cell::from(format!("{:.0}%", fill * 100.0))
+                ])
+            }).collect::<Vec<Row>>())
+                .header(Row::new(vec![tr("column-name"), tr("column-slots"), tr("tab-items"), tr("column-percent-full")]).style(header_style))
+                .block(Block::default().borders(Borders::ALL).title(tr("my-shelves")))
+                .widths(&SHELF_COLUMNS),
+            tab => Table::new(self.visible_items().into_iter().map(|i| Row::new(vec![
+                Cell::from(i.object_id.to_string()),
+                Cell::from(i.description.clone()),
+                Cell::from(i.shelf_id.clone()),
+                Cell::from(format_number(i.stock as u64)),
+                Cell::from(self.money_format.format(i.price as u64))
+            ])).collect::<Vec<Row>>())
+                .header(Row::new(vec![tr("column-id"), tr("column-description"), tr("column-shelf"), tr("column-stock"), tr("column-price")]).style(header_style))
+                .block(Block::default().borders(Borders::ALL).title(match tab {
+                    TabId::ShelfView(shelf_id) => tr_args("viewing-shelf", &[("shelf", shelf_id.as_str().into())]),
+                    _ => tr("all-items")
+                }))
+                .widths(&ITEM_COLUMNS)
+        };
+        f.render_stateful_widget(table.highlight_style(highlight).highlight_symbol("> "), chunks[1], &mut self.table);
+
+        let footer = match &self.status {
+            Some(status) => Paragraph::new(status.as_str()).style(Style::default().fg(Color::Yellow)),
+            None => Paragraph::new(tr("tui-keys"))
+        };
+        f.render_widget(footer, chunks[2]);
+
+        if let Some(form) = &self.form {
+            draw_form(f, form);
+        }
+    }
+}
+
+fn centered(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
+
+fn draw_form<B: Backend>(f: &mut Frame<B>, form: &Form) {
+    let label_width = form.fields.iter().map(|field| field.label.chars().count()).max().unwrap_or(0);
+    let mut lines = form.fields.iter().enumerate().map(|(index, field)| {
+        let style = if index == form.focus { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() };
+        Spans::from(vec![
+            Span::styled(format!("{:>width$}: ", field.label, width = label_width), style),
+            Span::raw(field.value.clone())
+        ])
+    }).collect::<Vec<Spans>>();
+    lines.push(Spans::from(""));
+    if let Some(error) = &form.error {
+        lines.push(Spans::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+    }
+    lines.push(Spans::from(tr("tui-form-keys")));
+
+    let area = centered(60, lines.len() as u16 + 2, f.size());
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(form.title())), area);
+
+    let focused = &form.fields[form.focus];
+    let cursor_x = area.x + 1 + label_width as u16 + 2 + focused.value.chars().count() as u16;
+    f.set_cursor(cursor_x.min(area.x + area.width - 2), area.y + 1 + form.focus as u16);
+}
+
+fn setup_terminal() -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    Terminal::new(CrosstermBackend::new(stdout))
+}
+
+pub(crate) async fn run_tui(connection: &Connection, money_format: &MoneyFormat) -> Result<(), CliError> {
+    // the terminal follows the language picked in the GUI
+    set_locale(LocaleChoice::from_tag(Session::load().locale.as_deref()).resolve());
+    let mut app = TuiApp::new(connection, money_format);
+    app.reload().await;
+    app.clamp_selection();
+
+    let _guard = TerminalGuard;
+    let mut terminal = setup_terminal().map_err(|e| CliError::Output(e.to_string()))?;
+    while !app.quit {
+        terminal.draw(|f| app.draw(f)).map_err(|e| CliError::Output(e.to_string()))?;
+        // read blocks until a key or a resize, both of which need a redraw
+        match event::read().map_err(|e| CliError::Output(e.to_string()))? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key).await,
+            _ => {}
+        }
+    }
+    terminal.show_cursor().map_err(|e| CliError::Output(e.to_string()))
+}
@@ -0,0 +1,106 @@
+//! Domain types returned by [`SimsClient`](crate::SimsClient).
+//!
+//! They mirror the prost messages in [`proto`](crate::proto) with clearer field names and
+//! convert to and from them with `From`, so code that already speaks the wire types can mix both.
+
+use crate::proto::{ItemInfo, ShelfInfo, SlotInfo};
+
+/// How many units a slot holds when the server does not say otherwise.
+pub const DEFAULT_SLOT_CAPACITY: u32 = 30;
+
+/// A shelf and the number of slots it is divided into.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shelf {
+    /// Identifier chosen when the shelf was created, unique per server.
+    pub id: String,
+    /// Number of slots on the shelf.
+    pub slots: u32
+}
+
+/// An item stocked on a shelf.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// Identifier assigned by the server.
+    pub id: u32,
+    /// Free-form name, shown as the description in the GUI.
+    pub name: String,
+    /// Shelf the item is stocked on.
+    pub shelf_id: String,
+    /// Units in stock.
+    pub stock: u32,
+    /// Unit price in minor currency units, e.g. cents.
+    pub price: u32
+}
+
+/// One slot of a shelf and how full it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot {
+    /// Position of the slot on its shelf, starting at 0.
+    pub number: u32,
+    /// Units the slot can hold.
+    pub capacity: u32,
+    /// Units currently in the slot.
+    pub item_count: u32
+}
+
+impl Shelf {
+    /// Lays the given items out over the shelf's slots.
+    ///
+    /// The server has no slot query, so slots are derived client side with one item per slot
+    /// in the order the items are given. Slots past the last item are empty and items past the
+    /// last slot are left out.
+    pub fn layout_slots(&self, items: &[Item], capacity: u32) -> Vec<Slot> {
+        (0..self.slots).map(|number| Slot {
+            number,
+            capacity,
+            item_count: items.get(number as usize).map(|i| i.stock).unwrap_or(0)
+        }).collect()
+    }
+}
+
+/// Fraction of the total capacity of `slots` that is in use.
+///
+/// Overfull slots can push this past 1.0. An empty list or zero capacity gives 0.0.
+pub fn slot_fill(slots: &[Slot]) -> f32 {
+    let capacity = slots.iter().map(|s| s.capacity as u64).sum::<u64>();
+    if capacity == 0 {
+        return 0.0;
+    }
+    slots.iter().map(|s| s.item_count as u64).sum::<u64>() as f32 / capacity as f32
+}
+
+impl From<ShelfInfo> for Shelf {
+    fn from(shelf: ShelfInfo) -> Self {
+        Shelf { id: shelf.shelf_id, slots: shelf.shelf_count }
+    }
+}
+
+impl From<Shelf> for ShelfInfo {
+    fn from(shelf: Shelf) -> Self {
+        ShelfInfo { shelf_id: shelf.id, shelf_count: shelf.slots }
+    }
+}
+
+impl From<ItemInfo> for Item {
+    fn from(item: ItemInfo) -> Self {
+        Item { id: item.object_id, name: item.description, shelf_id: item.shelf_id, stock: item.stock, price: item.price }
+    }
+}
+
+impl From<Item> for ItemInfo {
+    fn from(item: Item) -> Self {
+        ItemInfo { description: item.name, object_id: item.id, shelf_id: item.shelf_id, price: item.price, stock: item.stock }
+    }
+}
+
+impl From<SlotInfo> for Slot {
+    fn from(slot: SlotInfo) -> Self {
+        Slot { number: slot.slot_num, capacity: slot.capacity, item_count: slot.item_count }
+    }
+}
+
+impl From<Slot> for SlotInfo {
+    fn from(slot: Slot) -> Self {
+        SlotInfo { slot_num: slot.number, capacity: slot.capacity, item_count: slot.item_count }
+    }
+}
@@ -1,15 +1,33 @@
 use std::sync::mpsc;
-use crate::frontend::{EditTarget, GetItemsResponse, LoginResult, RpcCallResult, TabId};
+use crate::frontend::{EditTarget, GetItemsResponse, LoginResult, RpcCallResult, ShelfSortColumn, TabId};
 use crate::frontend::sims_ims_frontend::{ShelfInfo, Shelves, Token};
+use crate::i18n::LocaleChoice;
+use crate::themes::{ThemeChoice, UiScale};
+use crate::shortcuts::Shortcut;
+use crate::views::Pane;
 
 #[derive(Debug, Clone)]
 pub(crate) enum Message {
     LoginButtonClicked,
     RegisterButtonClicked,
+    ShowRegistration,
+    CloseRegistration,
+    Registered(Result<Token, LoginResult>),
+    ServerInputChanged(String),
+    ProfileSelected(String),
+    SwitchProfile(String),
     UsernameInputChanged(String),
     PasswordInputChanged(String),
+    RememberMeToggled(bool),
     Authenticated(Result<Token, LoginResult>),
     TabSelected(TabId),
+    NavigateBack,
+    NavigateForward,
+    TogglePinTab(TabId),
+    ToggleSplit,
+    SplitTabSelected(TabId),
+    TabMoveStarted(TabId),
+    TabMovedTo(TabId),
     CloseShelf(TabId),
     OpenShelf(TabId),
     StartEditing(EditTarget),
@@ -18,8 +36,30 @@ pub(crate) enum Message {
     UpdateShelves(Option<String>),
     UpdatedItems(Result<GetItemsResponse, RpcCallResult>),
     UpdateItems(Option<String>),
+    UpdateAll,
     CreateObjectNameInputChanged(String),
     ShelfSlotCountInputChanged(String),
     CreateTarget,
-    SlotPicked(String)
+    ShelfSortChanged(ShelfSortColumn),
+    ShowSuggestions,
+    CloseSuggestions,
+    SuggestionOpened(usize),
+    SuggestionDismissed(usize),
+    ShowSettings,
+    CloseSettings,
+    ThemeSelected(ThemeChoice),
+    LocaleSelected(LocaleChoice),
+    UiScaleSelected(UiScale),
+    ShowAccount,
+    CloseAccount,
+    ConfirmPasswordChanged(String),
+    Logout,
+    CheckSystemTheme,
+    SystemThemeDetected(bool),
+    Shortcut(Shortcut),
+    PaletteQueryChanged(String),
+    PaletteSelected(usize),
+    ItemFilterChanged(Pane, String),
+    WindowResized(u32, u32),
+    CloseRequested
 }
\ No newline at end of file
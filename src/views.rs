@@ -1,13 +1,23 @@
 use crate::assets::get_icon;
-use crate::frontend::EditTarget::{NewItem, NewShelf};
-use crate::frontend::{create_tab, EditTarget, TabId};
-use crate::styles::Fab;
+use crate::credentials::can_remember;
+use crate::dashboard::{InventoryStats, SlotHeatmap, StockHistogram, UtilisationChart};
+use crate::frontend::{create_tab, DEFAULT_SLOT_CAPACITY, EditTarget, shelf_slots, ShelfSortColumn, slot_fill, TabId};
+use crate::frontend::sims_ims_frontend::ItemInfo;
+use crate::i18n::{format_number, LocaleChoice, tr, tr_args};
+use crate::password::{strength, Strength};
+use crate::palette::{edit_input_id, login_input_id, palette_entries, palette_input_id, search_input_id};
+use crate::shortcuts::Shortcut;
+use crate::styles::{alert, Fab, modal, table_header, table_row_style};
+use crate::suggestions::Suggestion;
+use crate::themes::{ThemeChoice, UI_SCALES, UiScale};
 use crate::ui_messages::Message;
-use crate::ui_messages::Message::{StartEditing, StopEditing, UpdateItems, UpdateShelves};
-use crate::{assets, ui_messages, ClientState};
+use crate::ui_messages::Message::{StartEditing, StopEditing};
+use crate::{assets, ui_messages};
+use crate::app::ClientState;
 use iced::widget::image as iced_image;
+use iced::widget::scrollable;
 use iced::widget::{
-    button, column, row, text, Button, Column, Container, Image, Row, Rule, Scrollable, Space,
+    button, column, row, text, Button, Canvas, Checkbox, Column, Container, Image, ProgressBar, Row, Rule, Scrollable, Space,
     Text, TextInput,PickList
 };
 use iced::Length;
@@ -17,6 +27,32 @@ use iced_aw::floating_element::FloatingElement;
 use iced_aw::{Card, Modal};
 use std::iter;
 
+// the saved login is encrypted with the user's passphrase, without one there is nothing to offer
+fn remember_me_toggle(state: &ClientState) -> Element<Message> {
+    if can_remember() {
+        Checkbox::new(state.remember_me, tr("remember-me"), Message::RememberMeToggled).into()
+    } else {
+        text(tr("remember-me-unavailable")).size(16).into()
+    }
+}
+
+fn profile_picker(state: &ClientState) -> Element<Message> {
+    if state.profiles.is_empty() {
+        return Space::with_height(Length::Units(0)).into();
+    }
+
+    let names = state.profiles.iter().map(|p| p.name.clone()).collect::<Vec<String>>();
+    let selected = Some(state.profile.name.clone()).filter(|n| names.contains(n));
+    Container::new(
+        PickList::new(names, selected, Message::ProfileSelected)
+            .placeholder(tr("choose-profile"))
+            .width(Fill)
+            .padding(10),
+    )
+    .padding([0, 0, 5, 0])
+    .into()
+}
+
 pub(crate) fn unauthenticated_view<'a>(
     state: &ClientState,
     password: &String,
@@ -42,32 +78,45 @@ pub(crate) fn unauthenticated_view<'a>(
                         .center_x(),
                 )
                 .push(Rule::horizontal(20))
+                .push(profile_picker(state))
                 .push(
-                    TextInput::new("Username", &state.username, Message::UsernameInputChanged)
+                    TextInput::new(&tr("server"), &state.profile.server, Message::ServerInputChanged)
+                        .on_submit(Message::LoginButtonClicked)
                         .padding(10),
                 )
                 .push(
-                    TextInput::new("Password", password, Message::PasswordInputChanged)
+                    TextInput::new(&tr("username"), &state.username, Message::UsernameInputChanged)
+                        .id(login_input_id())
+                        .on_submit(Message::LoginButtonClicked)
+                        .padding(10),
+                )
+                .push(
+                    TextInput::new(&tr("password"), password, Message::PasswordInputChanged)
+                        .on_submit(Message::LoginButtonClicked)
                         .padding(10)
                         .password(),
                 )
+                .push(Space::with_height(Length::Units(5)))
+                .push(remember_me_toggle(state))
                 .push(Rule::horizontal(20))
                 .push(
-                    Button::new("Login")
+                    Button::new(text(tr("login")))
                         .on_press(Message::LoginButtonClicked)
                         .width(Fill),
                 )
                 .push(Space::with_height(Length::Units(3)))
                 .push(
-                    Button::new("Register & Login")
-                        .on_press(Message::RegisterButtonClicked)
+                    Button::new(text(tr("create-account")))
+                        .on_press(Message::ShowRegistration)
+                        .style(theme::Button::Secondary)
                         .width(Fill),
                 )
                 .push(
-                    Container::new(Text::new(match error_message {
-                        Some(message) => message,
-                        None => "",
-                    }))
+                    Container::new(match error_message {
+                        Some(message) => alert_message(message),
+                        None => Space::with_height(Length::Units(0)).into(),
+                    })
+                    .width(Fill)
                     .height(Length::Units(50))
                     .center_x()
                     .center_y(),
@@ -84,179 +133,689 @@ pub(crate) fn unauthenticated_view<'a>(
         .into()
 }
 
-pub(crate) fn inventory_view(state: &ClientState) -> Element<Message> {
-    let page_content: Element<'_, Message> = match state.current_tab.last().unwrap_or_default() {
-        TabId::AllShelves => Column::new()
-            .push(
-                Row::new().push(
-                    Container::new(Text::new("My Shelves").size(30))
-                        .width(Length::Fill)
+fn alert_message<'a>(message: &str) -> Element<'a, Message> {
+    Container::new(text(message.to_owned()))
+        .width(Fill)
+        .padding(5)
+        .style(theme::Container::Custom(alert))
+        .into()
+}
+
+// gives every modal card the same border so it stands out from the page behind it
+fn modal_frame<'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    Container::new(content)
+        .padding(1)
+        .style(theme::Container::Custom(modal))
+        .into()
+}
+
+fn header_row<'a>(content: impl Into<Element<'a, Message>>) -> Container<'a, Message> {
+    Container::new(content)
+        .width(Fill)
+        .padding(5)
+        .style(theme::Container::Custom(table_header))
+}
+
+pub(crate) fn registration_view<'a>(
+    state: &ClientState,
+    password: &String,
+    confirm_password: &String,
+    error_message: &'a Option<String>,
+) -> Element<'a, Message> {
+    let password_strength = strength(password);
+    let meter_style = match password_strength {
+        Strength::VeryWeak | Strength::Weak => theme::ProgressBar::Danger,
+        Strength::Fair => theme::ProgressBar::Primary,
+        Strength::Strong | Strength::VeryStrong => theme::ProgressBar::Success,
+    };
+
+    let policy = &state.profile.password_policy;
+    let rules = policy.rules().into_iter().fold(Column::new(), |c, rule| {
+        let met = rule.is_met(password);
+        c.push(text(format!("{} {}", if met { "\u{2713}" } else { "\u{2717}" }, rule.description())).size(16))
+    });
+
+    let passwords_match = !confirm_password.is_empty() && password == confirm_password;
+    let mut submit = Button::new(text(tr("create-account"))).width(Fill);
+    if policy.is_satisfied(password) && passwords_match && !state.username.trim().is_empty() {
+        submit = submit.on_press(Message::RegisterButtonClicked);
+    }
+
+    let elements = Row::new()
+        .push(Space::with_width(Length::FillPortion(3)))
+        .push(
+            Column::new()
+                .push(
+                    Container::new(Text::new(tr("create-account")).size(30))
+                        .width(Fill)
                         .center_x(),
-                ),
-            )
-            .push(row![
-                Text::new("Name").width(Length::FillPortion(3)),
-                Text::new("# of Slots").width(Length::FillPortion(3)),
-                Text::new("Actions").width(Length::FillPortion(1))
+                )
+                .push(Rule::horizontal(20))
+                .push(
+                    TextInput::new(&tr("server"), &state.profile.server, Message::ServerInputChanged)
+                        .on_submit(Message::RegisterButtonClicked)
+                        .padding(10),
+                )
+                .push(
+                    TextInput::new(&tr("username"), &state.username, Message::UsernameInputChanged)
+                        .id(login_input_id())
+                        .on_submit(Message::RegisterButtonClicked)
+                        .padding(10),
+                )
+                .push(
+                    TextInput::new(&tr("password"), password, Message::PasswordInputChanged)
+                        .on_submit(Message::RegisterButtonClicked)
+                        .padding(10)
+                        .password(),
+                )
+                .push(
+                    TextInput::new(&tr("confirm-password"), confirm_password, Message::ConfirmPasswordChanged)
+                        .on_submit(Message::RegisterButtonClicked)
+                        .padding(10)
+                        .password(),
+                )
+                .push(Space::with_height(Length::Units(5)))
+                .push(row![
+                    ProgressBar::new(0.0..=4.0, password_strength.score())
+                        .height(Length::Units(8))
+                        .style(meter_style)
+                        .width(Length::FillPortion(3)),
+                    Space::with_width(Length::Units(10)),
+                    text(password_strength.label()).size(16).width(Length::FillPortion(1))
+                ])
+                .push(Space::with_height(Length::Units(5)))
+                .push(rules)
+                .push(text(if confirm_password.is_empty() || passwords_match { String::new() } else { tr("error-password-mismatch") }).size(16))
+                .push(Rule::horizontal(20))
+                .push(submit)
+                .push(Space::with_height(Length::Units(3)))
+                .push(
+                    Button::new(text(tr("back-to-login")))
+                        .on_press(Message::CloseRegistration)
+                        .style(theme::Button::Secondary)
+                        .width(Fill),
+                )
+                .push(
+                    Container::new(match error_message {
+                        Some(message) => alert_message(message),
+                        None => Space::with_height(Length::Units(0)).into(),
+                    })
+                    .width(Fill)
+                    .height(Length::Units(50))
+                    .center_x()
+                    .center_y(),
+                )
+                .width(Length::FillPortion(2)),
+        )
+        .push(Space::with_width(Length::FillPortion(3)));
+
+    Container::new(elements)
+        .width(Fill)
+        .height(Fill)
+        .center_x()
+        .center_y()
+        .into()
+}
+
+// each side of the split keeps its own scroll position through a separate scrollable id
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Pane {
+    Primary,
+    Secondary
+}
+
+impl Pane {
+    fn scroll_id(&self) -> scrollable::Id {
+        match self {
+            Pane::Primary => scrollable::Id::new("primary-pane"),
+            Pane::Secondary => scrollable::Id::new("secondary-pane")
+        }
+    }
+}
+
+pub(crate) fn suggestions_view(suggestions: &[Suggestion]) -> Element<Message> {
+    let header = row![
+        Container::new(Text::new(tr("suggestions")).size(30))
+            .width(Length::Fill)
+            .center_x(),
+        button(text(tr("back"))).on_press(Message::CloseSuggestions)
+    ];
+
+    let suggestion_list: Element<'_, Message> = if suggestions.is_empty() {
+        Container::new(text(tr("no-suggestions")))
+            .width(Fill)
+            .center_x()
+            .into()
+    } else {
+        Scrollable::new(
+            suggestions
+                .iter()
+                .enumerate()
+                .map(|(i, suggestion)| {
+                    Container::new(row![
+                        text(suggestion.to_string()).width(Length::Fill),
+                        Button::new(text(tr("open"))).on_press(Message::SuggestionOpened(i)),
+                        Space::with_width(Length::Units(5)),
+                        Button::new(text(tr("dismiss"))).on_press(Message::SuggestionDismissed(i))
+                    ])
+                    .height(Length::Units(40))
+                    .center_y()
+                })
+                .fold(Column::new(), |c, v| c.push(v))
+                .width(Length::Fill),
+        )
+        .into()
+    };
+
+    Column::new()
+        .push(Container::new(header).width(Fill).padding(5))
+        .push(Rule::horizontal(2))
+        .push(Container::new(suggestion_list).width(Fill).height(Fill).padding(5))
+        .into()
+}
+
+pub(crate) fn settings_view(state: &ClientState) -> Element<Message> {
+    let header = row![
+        Container::new(Text::new(tr("settings")).size(30))
+            .width(Length::Fill)
+            .center_x(),
+        button(text(tr("back"))).on_press(Message::CloseSettings)
+    ];
+
+    let choices = [ThemeChoice::Light, ThemeChoice::Dark, ThemeChoice::System]
+        .into_iter()
+        .chain(state.palettes.iter().map(|p| ThemeChoice::Custom(p.name.clone())))
+        .collect::<Vec<ThemeChoice>>();
+
+    let theme_row = row![
+        text(tr("setting-theme")).width(Length::Units(150)),
+        PickList::new(choices, Some(state.theme_choice.clone()), Message::ThemeSelected).width(Length::Units(250))
+    ]
+    .spacing(10);
+
+    let language_row = row![
+        text(tr("setting-language")).width(Length::Units(150)),
+        PickList::new(LocaleChoice::all(), Some(state.locale_choice.clone()), Message::LocaleSelected).width(Length::Units(250))
+    ]
+    .spacing(10);
+
+    let scale_row = row![
+        text(tr("setting-scale")).width(Length::Units(150)),
+        PickList::new(UI_SCALES.iter().map(|s| UiScale(*s)).collect::<Vec<UiScale>>(), Some(state.ui_scale), Message::UiScaleSelected).width(Length::Units(250))
+    ]
+    .spacing(10);
+
+    let palettes_hint = match crate::themes::themes_path() {
+        Some(path) => tr_args("palettes-path", &[("path", path.display().to_string().into())]),
+        None => tr("palettes-unavailable"),
+    };
+
+    Column::new()
+        .push(Container::new(header).width(Fill).padding(5))
+        .push(Rule::horizontal(2))
+        .push(
+            Container::new(column![
+                theme_row,
+                Space::with_height(Length::Units(5)),
+                text(palettes_hint).size(16),
+                Space::with_height(Length::Units(10)),
+                language_row,
+                Space::with_height(Length::Units(10)),
+                scale_row,
+                Space::with_height(Length::Units(5)),
+                text(tr("scale-hint")).size(16)
             ])
-            .push(Rule::horizontal(2))
-            .push(
-                // list all shelves
-                Scrollable::new(state.shelves.iter().fold(Column::new(), |c, s| {
-                    c.push(
-                        Container::new(row![
-                            Text::new(s.shelf_id.clone()).width(Length::FillPortion(3)),
-                            Text::new(format!("Slots: {}", s.shelf_count))
-                                .width(Length::FillPortion(3)),
-                            Container::new(
-                                Button::new("Open")
-                                    .on_press(Message::OpenShelf(TabId::ShelfView(
-                                        s.shelf_id.clone()
-                                    )))
-                                    .width(Length::Shrink)
-                            )
-                            .width(Length::FillPortion(1))
-                        ])
-                        .height(Length::Units(40))
-                        .center_y(),
-                    )
-                })),
+                .width(Fill)
+                .height(Fill)
+                .padding(10),
+        )
+        .into()
+}
+
+fn switch_profile_picker(state: &ClientState) -> Element<Message> {
+    let names = state.profiles.iter()
+        .map(|p| p.name.clone())
+        .filter(|n| n != &state.profile.name)
+        .collect::<Vec<String>>();
+    if names.is_empty() {
+        return Space::with_width(Length::Units(0)).into();
+    }
+    PickList::new(names, None, Message::SwitchProfile)
+        .placeholder(tr("switch-profile"))
+        .into()
+}
+
+pub(crate) fn account_view(state: &ClientState) -> Element<Message> {
+    let header = row![
+        Container::new(Text::new(tr("account")).size(30))
+            .width(Length::Fill)
+            .center_x(),
+        button(text(tr("back"))).on_press(Message::CloseAccount)
+    ];
+
+    // the protocol has no way to change a password, so the screen only covers the session
+    let form = column![
+        text(tr_args("signed-in-as", &[
+            ("username", state.username.as_str().into()),
+            ("server", state.profile.server.as_str().into())
+        ])),
+        Rule::horizontal(20),
+        row![
+            switch_profile_picker(state),
+            Space::with_width(Length::Units(5)),
+            button(text(tr("logout"))).style(theme::Button::Destructive).on_press(Message::Logout)
+        ]
+    ]
+    .spacing(5)
+    .width(Length::Units(500));
+
+    Column::new()
+        .push(Container::new(header).width(Fill).padding(5))
+        .push(Rule::horizontal(2))
+        .push(Container::new(form).width(Fill).height(Fill).padding(10))
+        .into()
+}
+
+fn dashboard_view(state: &ClientState) -> Element<Message> {
+    let stats = InventoryStats::compute(&state.shelves, &state.all_items);
+
+    let stat = |label: String, value: String| {
+        Column::new()
+            .push(text(label))
+            .push(text(value).size(30))
+            .width(Length::FillPortion(1))
+    };
+
+    Column::new()
+        .push(
+            Container::new(text(tr("tab-dashboard")).size(30))
+                .width(Length::Fill)
+                .center_x(),
+        )
+        .push(row![
+            stat(tr("total-skus"), format_number(stats.total_skus as u64)),
+            stat(tr("total-units"), format_number(stats.total_units as u64)),
+            stat(tr("inventory-value"), state.money_format.format(stats.total_value))
+        ].padding(10))
+        .push(Rule::horizontal(2))
+        .push(row![
+            column![
+                text(tr("shelf-utilisation")),
+                Canvas::new(UtilisationChart { bars: stats.shelf_utilisation })
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+            ].width(Length::FillPortion(1)).spacing(5),
+            Space::with_width(Length::Units(20)),
+            column![
+                text(tr("stock-distribution")),
+                Canvas::new(StockHistogram { buckets: stats.stock_histogram })
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+            ].width(Length::FillPortion(1)).spacing(5)
+        ].padding(10).height(Length::Fill))
+        .into()
+}
+
+fn shelves_view(state: &ClientState, pane: Pane) -> Element<Message> {
+    let mut shelves = state
+        .shelves
+        .iter()
+        .map(|s| {
+            let slots = shelf_slots(s, state.all_items.get(&s.shelf_id).map(|i| i.as_slice()).unwrap_or(&[]), DEFAULT_SLOT_CAPACITY);
+            let fill = slot_fill(&slots);
+            (s, slots, fill)
+        })
+        .collect::<Vec<_>>();
+
+    match state.shelf_sort.column {
+        ShelfSortColumn::Name => shelves.sort_by(|a, b| a.0.shelf_id.cmp(&b.0.shelf_id)),
+        ShelfSortColumn::Slots => shelves.sort_by_key(|s| s.0.shelf_count),
+        ShelfSortColumn::PercentFull => shelves.sort_by(|a, b| a.2.total_cmp(&b.2)),
+    }
+    if state.shelf_sort.descending {
+        shelves.reverse();
+    }
+
+    let sort_header = |label: String, column: ShelfSortColumn| {
+        let indicator = match (state.shelf_sort.column == column, state.shelf_sort.descending) {
+            (false, _) => "",
+            (true, false) => " \u{25B2}",
+            (true, true) => " \u{25BC}",
+        };
+        button(text(format!("{}{}", label, indicator)))
+            .style(theme::Button::Text)
+            .padding(0)
+            .on_press(Message::ShelfSortChanged(column))
+    };
+
+    Column::new()
+        .push(
+            Row::new().push(
+                Container::new(Text::new(tr("my-shelves")).size(30))
+                    .width(Length::Fill)
+                    .center_x(),
+            ),
+        )
+        // the server reports no slots, so the heatmap and percentages come from Shelf::layout_slots
+        .push(
+            Container::new(Text::new(tr_args("slots-estimated", &[("capacity", format_number(DEFAULT_SLOT_CAPACITY as u64).into())])).size(14))
+                .width(Length::Fill)
+                .center_x(),
+        )
+        .push(header_row(row![
+            sort_header(tr("column-name"), ShelfSortColumn::Name).width(Length::FillPortion(3)),
+            sort_header(tr("column-slots"), ShelfSortColumn::Slots).width(Length::FillPortion(2)),
+            Text::new(tr("column-occupancy")).width(Length::FillPortion(3)),
+            sort_header(tr("column-percent-full"), ShelfSortColumn::PercentFull).width(Length::FillPortion(1)),
+            Text::new(tr("column-actions")).width(Length::FillPortion(1))
+        ]))
+        .push(Rule::horizontal(2))
+        .push(
+            // list all shelves
+            Scrollable::new(shelves.into_iter().enumerate().fold(Column::new(), |c, (i, (s, slots, fill))| {
+                c.push(
+                    Container::new(row![
+                        Text::new(s.shelf_id.clone()).width(Length::FillPortion(3)),
+                        Text::new(tr_args("slot-count", &[("count", format_number(s.shelf_count as u64).into())]))
+                            .width(Length::FillPortion(2)),
+                        Container::new(
+                            Canvas::new(SlotHeatmap { slots })
+                                .width(Length::Fill)
+                                .height(Length::Units(16))
+                        )
+                        .width(Length::FillPortion(3))
+                        .padding([0, 10, 0, 0]),
+                        Text::new(format!("{:.0}%", fill * 100.0)).width(Length::FillPortion(1)),
+                        Container::new(
+                            Button::new(text(tr("open")))
+                                .on_press(Message::OpenShelf(TabId::ShelfView(
+                                    s.shelf_id.clone()
+                                )))
+                                .width(Length::Shrink)
+                        )
+                        .width(Length::FillPortion(1))
+                    ])
+                    .height(Length::Units(40))
+                    .padding([0, 5])
+                    .center_y()
+                    .style(table_row_style(i)),
+                )
+            })).id(pane.scroll_id()),
+        )
+        .into()
+}
+
+fn pane_filter(state: &ClientState, pane: Pane) -> &str {
+    match pane {
+        Pane::Primary => &state.item_filter,
+        Pane::Secondary => &state.split_item_filter
+    }
+}
+
+fn search_bar(state: &ClientState, pane: Pane) -> Element<Message> {
+    let mut search = TextInput::new(&tr("search-items"), pane_filter(state, pane), move |filter| Message::ItemFilterChanged(pane, filter)).padding(5);
+    // only one input can own the id that Ctrl+F focuses
+    if let Pane::Primary = pane {
+        search = search.id(search_input_id());
+    }
+    Container::new(search)
+    .padding([0, 0, 5, 0])
+    .into()
+}
+
+fn item_matches(item: &ItemInfo, filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    filter.is_empty()
+        || item.description.to_lowercase().contains(&filter)
+        || item.object_id.to_string().starts_with(&filter)
+}
+
+fn palette_view(state: &ClientState) -> Element<Message> {
+    let palette = match &state.palette {
+        Some(p) => p,
+        None => return text("").into(),
+    };
+
+    let entries = palette_entries(state, &palette.query);
+    let selected = palette.selected.min(entries.len().saturating_sub(1));
+
+    let mut input = TextInput::new(&tr("palette-placeholder"), &palette.query, Message::PaletteQueryChanged)
+        .id(palette_input_id())
+        .padding(10);
+    if !entries.is_empty() {
+        input = input.on_submit(Message::PaletteSelected(selected));
+    }
+
+    let results = entries
+        .into_iter()
+        .enumerate()
+        .fold(Column::new(), |c, (i, entry)| {
+            c.push(
+                button(text(entry.label))
+                    .width(Length::Fill)
+                    .style(if i == selected { theme::Button::Primary } else { theme::Button::Text })
+                    .on_press(Message::PaletteSelected(i)),
             )
-            .into(),
+        });
+
+    modal_frame(
+        Card::new(text(tr("command-palette")).width(Length::Fill), column![input, Space::with_height(Length::Units(5)), results])
+            .width(Length::Units(500)),
+    )
+}
+
+fn navigation_button<'a>(icon: char, enabled: bool, message: Message) -> Button<'a, Message> {
+    let nav = button(get_icon(icon)).height(Length::Units(30));
+    if enabled {
+        nav.on_press(message)
+    } else {
+        nav
+    }
+}
+
+fn tab_content<'a>(state: &'a ClientState, tab: &'a TabId, pane: Pane) -> Element<'a, Message> {
+    match tab {
+        TabId::AllShelves => shelves_view(state, pane),
         TabId::AllItems => {
             Column::new()
                 .push(
-                    Container::new(text("All Items").size(30))
+                    Container::new(text(tr("all-items")).size(30))
                         .width(Length::Fill)
                         .center_x(),
                 )
-                .push(
+                .push(search_bar(state, pane))
+                .push(header_row(
                     row![
-                        text("Item").width(Length::FillPortion(1)),
+                        text(tr("column-item")).width(Length::FillPortion(1)),
                         // Rule::vertical(2),
-                        text("Description").width(Length::FillPortion(3)),
-                        text("Shelf").width(Length::FillPortion(1)),
+                        text(tr("column-description")).width(Length::FillPortion(3)),
+                        text(tr("column-shelf")).width(Length::FillPortion(1)),
                         // Rule::vertical(2),
-                        text("Price").width(Length::FillPortion(1)),
+                        text(tr("column-price")).width(Length::FillPortion(1)),
                         // Rule::vertical(2),
-                        text("Stock").width(Length::FillPortion(1))
+                        text(tr("column-stock")).width(Length::FillPortion(1))
                     ]
                     .height(Length::Shrink),
-                )
+                ))
                 .push(Rule::horizontal(2))
                 .push(Scrollable::new(
                     state
                         .all_items
                         .iter()
                         .flat_map(|(k, v)| v.iter().zip(iter::repeat(k)))
-                        .map(|(item, shelf)| {
+                        .filter(|(item, _)| item_matches(item, pane_filter(state, pane)))
+                        .enumerate()
+                        .map(|(i, (item, shelf))| {
                             Container::new(row![
                                 text(item.object_id.clone()).width(Length::FillPortion(1)),
                                 // Rule::vertical(2),
                                 text(item.description.clone()).width(Length::FillPortion(3)),
                                 text(shelf.clone()).width(Length::FillPortion(1)),
                                 // Rule::vertical(2),
-                                text(item.price).width(Length::FillPortion(1)),
+                                text(state.money_format.format(item.price as u64)).width(Length::FillPortion(1)),
                                 // Rule::vertical(2),
-                                text(item.stock).width(Length::FillPortion(1))
+                                text(format_number(item.stock as u64)).width(Length::FillPortion(1))
                             ])
                             .height(Length::Units(40))
+                            .padding([0, 5])
                             .center_y()
+                            .style(table_row_style(i))
                         })
                         .fold(Column::new(), |c, v| c.push(v))
                         .width(Length::Fill),
-                ))
+                ).id(pane.scroll_id()))
                 .into()
         }
+        TabId::Dashboard => dashboard_view(state),
         TabId::ShelfView(shelf_id) => {
             match state.all_items.get(shelf_id) {
                 Some(shelf_items) => {
                     Column::new()
                         .push(
-                            Container::new(text(format!("Viewing {}", shelf_id)).size(30))
+                            Container::new(text(tr_args("viewing-shelf", &[("shelf", shelf_id.as_str().into())])).size(30))
                                 .width(Length::Fill)
                                 .center_x(),
                         )
-                        .push(
+                        .push(search_bar(state, pane))
+                        .push(header_row(
                             row![
-                                text("ID").width(Length::FillPortion(1)),
+                                text(tr("column-id")).width(Length::FillPortion(1)),
                                 // Rule::vertical(2),
-                                text("Name").width(Length::FillPortion(4)),
+                                text(tr("column-name")).width(Length::FillPortion(4)),
                                 // Rule::vertical(2),
-                                text("Price").width(Length::FillPortion(1)),
+                                text(tr("column-price")).width(Length::FillPortion(1)),
                                 // Rule::vertical(2),
-                                text("Stock").width(Length::FillPortion(1))
+                                text(tr("column-stock")).width(Length::FillPortion(1))
                             ]
                             .height(Length::Shrink),
-                        )
+                        ))
                         .push(Rule::horizontal(2))
                         .push(Scrollable::new(
                             shelf_items
                                 .iter()
-                                .map(|s| {
+                                .filter(|s| item_matches(s, pane_filter(state, pane)))
+                                .enumerate()
+                                .map(|(i, s)| {
                                     Container::new(row![
                                         text(s.object_id.clone()).width(Length::FillPortion(1)),
                                         // Rule::vertical(2),
                                         text(s.description.clone()).width(Length::FillPortion(4)),
                                         // Rule::vertical(2),
-                                        text(s.price).width(Length::FillPortion(1)),
+                                        text(state.money_format.format(s.price as u64)).width(Length::FillPortion(1)),
                                         // Rule::vertical(2),
-                                        text(s.stock).width(Length::FillPortion(1))
+                                        text(format_number(s.stock as u64)).width(Length::FillPortion(1))
                                     ])
                                     .height(Length::Units(40))
+                                    .padding([0, 5])
                                     .center_y()
+                                    .style(table_row_style(i))
                                 })
                                 .fold(Column::new(), |c, v| c.push(v))
                                 .width(Length::Fill),
                         ))
                         .into()
                 }
-                None => text("Invalid shelf tab").into(),
+                None => text(tr("invalid-shelf-tab")).into(),
             }
         }
+    }
+}
+
+pub(crate) fn inventory_view(state: &ClientState) -> Element<Message> {
+    let page_content: Element<'_, Message> = match &state.split_tab {
+        None => tab_content(state, state.tab_history.current(), Pane::Primary),
+        Some(split_tab) => {
+            let open_tabs = state.tabs.iter().cloned().collect::<Vec<TabId>>();
+            let secondary = column![
+                row![
+                    PickList::new(open_tabs, Some(split_tab.clone()), Message::SplitTabSelected).width(Length::Fill),
+                    Space::with_width(Length::Units(2)),
+                    button(get_icon('\u{F116}')).on_press(ClientState::refresh_message_for(split_tab)),
+                    Space::with_width(Length::Units(2)),
+                    button(get_icon('\u{F62A}')).on_press(Message::ToggleSplit)
+                ]
+                .padding(5),
+                tab_content(state, split_tab, Pane::Secondary)
+            ];
+            row![
+                Container::new(tab_content(state, state.tab_history.current(), Pane::Primary)).width(Length::FillPortion(1)),
+                Rule::vertical(2),
+                Container::new(secondary).width(Length::FillPortion(1))
+            ]
+            .into()
+        }
     };
 
+    let current = state.tab_history.current();
+    let moving = state.moving_tab.is_some();
     let mut tabs = state
         .tabs
         .iter()
-        .map(|tab_info| match tab_info {
-            TabId::AllShelves => create_tab(
-                tab_info.clone(),
-                "Shelves".to_owned(),
-                false,
-                Some('\u{F685}'),
-            ),
-            TabId::AllItems => create_tab(
-                tab_info.clone(),
-                "Items".to_owned(),
-                false,
-                Some('\u{F7D3}'),
-            ),
-            TabId::ShelfView(shelf_id) => {
-                create_tab(tab_info.clone(), shelf_id.clone(), true, Some('\u{F1C8}'))
+        .map(|tab_info| {
+            let pinned = state.pinned_tabs.contains(tab_info);
+            let active = tab_info == current;
+            match tab_info {
+                TabId::AllShelves => create_tab(
+                    tab_info.clone(),
+                    tr("tab-shelves"),
+                    false,
+                    Some('\u{F685}'),
+                    pinned,
+                    active,
+                    moving,
+                ),
+                TabId::AllItems => create_tab(
+                    tab_info.clone(),
+                    tr("tab-items"),
+                    false,
+                    Some('\u{F7D3}'),
+                    pinned,
+                    active,
+                    moving,
+                ),
+                TabId::Dashboard => create_tab(
+                    tab_info.clone(),
+                    tr("tab-dashboard"),
+                    false,
+                    Some('\u{F17E}'),
+                    pinned,
+                    active,
+                    moving,
+                ),
+                TabId::ShelfView(shelf_id) => {
+                    create_tab(tab_info.clone(), shelf_id.clone(), true, Some('\u{F1C8}'), pinned, active, moving)
+                }
             }
         })
-        .fold(Row::new(), |tabs_container, tab| {
+        .fold(row![
+            navigation_button('\u{F12F}', state.tab_history.can_go_back(), Message::NavigateBack),
+            Space::with_width(Length::Units(2)),
+            navigation_button('\u{F138}', state.tab_history.can_go_forward(), Message::NavigateForward),
+            Space::with_width(Length::Units(5)),
+        ], |tabs_container, tab| {
             tabs_container
                 .push(Space::with_width(Length::Units(2)))
                 .push(tab)
         })
-        .push(Space::with_width(Length::Fill));
+        .push(Space::with_width(Length::Units(5)))
+        .push(text(if moving { tr("tab-move-hint") } else { String::new() }).size(14))
+        .push(Space::with_width(Length::Fill))
+        .push(button(get_icon('\u{F62A}')).on_press(Message::ToggleSplit))
+        .push(Space::with_width(Length::Units(2)))
+        .push(button(get_icon('\u{F4D7}')).on_press(Message::ShowAccount))
+        .push(Space::with_width(Length::Units(2)))
+        .push(button(get_icon('\u{F468}')).on_press(Message::ShowSuggestions))
+        .push(Space::with_width(Length::Units(2)))
+        .push(button(get_icon('\u{F3E5}')).on_press(Message::ShowSettings))
+        .push(Space::with_width(Length::Units(2)));
 
-    tabs = match state.current_tab.last().unwrap_or_default() {
-        TabId::AllShelves => tabs.push(button(get_icon('\u{F116}')).on_press(UpdateShelves(None))),
-        TabId::AllItems => tabs.push(button(get_icon('\u{F116}')).on_press(UpdateItems(None))),
-        TabId::ShelfView(shelf_id) => {
-            tabs.push(button(get_icon('\u{F116}')).on_press(UpdateItems(Some(shelf_id.clone()))))
-        }
-        _ => tabs,
-    };
+    tabs = tabs.push(button(get_icon('\u{F116}')).on_press(state.refresh_message()));
 
     let page = Column::new()
         .push(Container::new(tabs).width(Fill).height(Shrink).padding(5))
         .push(Container::new(page_content).width(Fill).height(Fill));
 
-    Modal::new(
+    let edit_modal = Modal::new(
         state.edit_item.is_some(),
         FloatingElement::new(Container::new(page).width(Fill).height(Fill), || {
             Button::new(
@@ -269,78 +828,21 @@ pub(crate) fn inventory_view(state: &ClientState) -> Element<Message> {
             .style(theme::Button::Custom(Box::new(Fab)))
             .width(Length::Units(50))
             .height(Length::Units(50))
-            .on_press(match state.current_tab.last().unwrap_or_default() {
-                TabId::AllShelves => StartEditing(NewShelf {
-                    shelf_name: "".to_owned(),
-                    slots: "0".to_owned(),
-                    error_message: None,
-                }),
-                TabId::AllItems => StartEditing(NewItem { shelf_id: "".to_owned(), item_name: "".to_owned(), item_count: "0".to_owned(), error_message: None }),
-                TabId::ShelfView(shelf_id) => StartEditing(NewItem {
-                    shelf_id: shelf_id.clone(),
-                    item_name: "".to_owned(),
-                    item_count: "0".to_owned(),
-                    error_message: None
-                }),
-            })
+            .on_press(StartEditing(state.new_edit_target()))
             .into()
         }),
         move || match state.edit_item.as_ref() {
-            None => text("Nothing to edit").into(),
+            None => text(tr("nothing-to-edit")).into(),
             Some(target) => match target {
-                EditTarget::EditItem { shelf_id, item_id } => text(format!(
-                    "Editing item with id {} in shelf with id {}",
-                    item_id, shelf_id
-                ))
+                EditTarget::EditItem { shelf_id, item_id } => text(tr_args("editing-item", &[
+                    ("item", item_id.as_str().into()),
+                    ("shelf", shelf_id.as_str().into())
+                ]))
                 .into(),
-                EditTarget::NewItem { shelf_id, item_name, item_count, error_message } => {
-                        let elements = state.shelves.iter().map(|s|s.shelf_id.clone()).collect::<Vec<String>>();
-                        println!("Shelves {:?}", elements);
-                        let mut card_content = column![
-                            row![
-                                Text::new("Shelf").width(Length::Units(60)),
-                                TextInput::new("Enter a shelf id", shelf_id, Message::SlotPicked)
-                            ],
-                            Space::with_height(Length::Units(5)),
-                            row![
-                                Text::new("Name").width(Length::Units(60)),
-                                TextInput::new(
-                                    "Item name",
-                                    item_name,
-                                    Message::CreateObjectNameInputChanged
-                                )
-                            ],
-                            Space::with_height(Length::Units(5)),
-                            row![
-                                Text::new("Stock").width(Length::Units(60)),
-                                TextInput::new(
-                                    "Item Stock",
-                                    &item_count,
-                                    Message::ShelfSlotCountInputChanged
-                                )
-                            ],
-                            Space::with_height(Length::Units(5)),
-                        ];
-                        if let Some(message) = error_message {
-                            card_content = card_content
-                                .push(text(message))
-                                .push(Space::with_height(Length::Units(5)));
-                        }
-                        card_content = card_content.push(row![
-                            Space::with_width(Length::FillPortion(5)),
-                            Button::new("Create")
-                                .width(Length::FillPortion(1))
-                                .on_press(Message::CreateTarget)
-                        ]);
-
-                        Card::new(text("New Item").width(Length::Fill), card_content)
-                            .width(Length::Units(400))
-                            .into()
-                },
-                EditTarget::EditSlot { shelf_id, slot_id } => text(format!(
-                    "Editing slot with id {} in shelf with id {}",
-                    slot_id, shelf_id
-                ))
+                EditTarget::EditSlot { shelf_id, slot_id } => text(tr_args("editing-slot", &[
+                    ("slot", slot_id.as_str().into()),
+                    ("shelf", shelf_id.as_str().into())
+                ]))
                 .into(),
                 EditTarget::NewShelf {
                     shelf_name,
@@ -349,46 +851,53 @@ pub(crate) fn inventory_view(state: &ClientState) -> Element<Message> {
                 } => {
                     let mut card_content = column![
                         row![
-                            Text::new("Name").width(Length::Units(60)),
+                            Text::new(tr("field-name")).width(Length::Units(60)),
                             TextInput::new(
-                                "Shelf name",
+                                &tr("placeholder-shelf-name"),
                                 shelf_name,
                                 Message::CreateObjectNameInputChanged
                             )
+                            .id(edit_input_id())
+                            .on_submit(Message::CreateTarget)
                         ],
                         Space::with_height(Length::Units(5)),
                         row![
-                            Text::new("Slots").width(Length::Units(60)),
+                            Text::new(tr("field-slots")).width(Length::Units(60)),
                             TextInput::new(
-                                "Slots",
+                                &tr("field-slots"),
                                 &format!("{}", slots),
                                 Message::ShelfSlotCountInputChanged
                             )
+                            .on_submit(Message::CreateTarget)
                         ],
                         Space::with_height(Length::Units(5)),
                     ];
                     if let Some(message) = error_message {
                         card_content = card_content
-                            .push(text(message))
+                            .push(alert_message(message))
                             .push(Space::with_height(Length::Units(5)));
                     }
                     card_content = card_content.push(row![
                         Space::with_width(Length::FillPortion(5)),
-                        Button::new("Create")
+                        Button::new(text(tr("create")))
                             .width(Length::FillPortion(1))
                             .on_press(Message::CreateTarget)
                     ]);
 
-                    Card::new(text("New Shelf").width(Length::Fill), card_content)
-                        .width(Length::Units(400))
-                        .into()
+                    modal_frame(
+                        Card::new(text(tr("new-shelf")).width(Length::Fill), card_content)
+                            .width(Length::Units(400)),
+                    )
                 }
                 EditTarget::EditShelf { shelf_id } => {
-                    text(format!("Editing shelf with id {}", shelf_id)).into()
+                    text(tr_args("editing-shelf", &[("shelf", shelf_id.as_str().into())])).into()
                 }
             },
         },
     )
-    .backdrop(StopEditing)
-    .into()
+    .backdrop(StopEditing);
+
+    Modal::new(state.palette.is_some(), edit_modal, move || palette_view(state))
+        .backdrop(Message::Shortcut(Shortcut::Escape))
+        .into()
 }
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::time::{Duration, SystemTime};
+use serde::Serialize;
+use crate::cli::{CliError, Connection, list_items};
+use crate::frontend::read_shelves;
+use crate::frontend::sims_ims_frontend::ItemInfo;
+use crate::money::MoneyFormat;
+use crate::output::OutputFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WatchEvent {
+    ShelfAdded,
+    ItemAdded,
+    ItemRemoved,
+    StockChanged,
+    PriceChanged,
+    LowStock,
+    Restocked
+}
+
+// field names are listed in the README, old and new are stock counts or prices in minor units depending on the event
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct WatchEventRecord {
+    pub(crate) time: String,
+    pub(crate) event: WatchEvent,
+    pub(crate) shelf_id: String,
+    pub(crate) item_id: Option<u32>,
+    pub(crate) name: Option<String>,
+    pub(crate) old: Option<u32>,
+    pub(crate) new: Option<u32>
+}
+
+impl WatchEventRecord {
+    fn shelf(time: &str, event: WatchEvent, shelf_id: &str, slots: u32) -> WatchEventRecord {
+        WatchEventRecord { time: time.to_owned(), event, shelf_id: shelf_id.to_owned(), item_id: None, name: None, old: None, new: Some(slots) }
+    }
+
+    fn item(time: &str, event: WatchEvent, item: &ItemInfo, old: Option<u32>, new: Option<u32>) -> WatchEventRecord {
+        WatchEventRecord {
+            time: time.to_owned(),
+            event,
+            shelf_id: item.shelf_id.clone(),
+            item_id: Some(item.object_id),
+            name: Some(item.description.clone()),
+            old,
+            new
+        }
+    }
+
+    fn is_alert(&self) -> bool {
+        self.event == WatchEvent::LowStock
+    }
+
+    fn describe(&self, money_format: &MoneyFormat) -> String {
+        let item = || format!("item {} {} on {}", self.item_id.unwrap_or_default(), self.name.clone().unwrap_or_default(), self.shelf_id);
+        let change = |format: &dyn Fn(u32) -> String| format!("{} -> {}",
+            self.old.map(format).unwrap_or_default(),
+            self.new.map(format).unwrap_or_default());
+        match self.event {
+            WatchEvent::ShelfAdded => format!("shelf {} added with {} slots", self.shelf_id, self.new.unwrap_or_default()),
+            WatchEvent::ItemAdded => format!("{} added", item()),
+            WatchEvent::ItemRemoved => format!("{} removed", item()),
+            WatchEvent::StockChanged => format!("{} stock {}", item(), change(&|n| n.to_string())),
+            WatchEvent::PriceChanged => format!("{} price {}", item(), change(&|p| money_format.format(p as u64))),
+            WatchEvent::LowStock => format!("{} is low on stock, {} left", item(), self.new.unwrap_or_default()),
+            WatchEvent::Restocked => format!("{} is back to {} in stock", item(), self.new.unwrap_or_default())
+        }
+    }
+}
+
+struct Snapshot {
+    shelves: BTreeMap<String, u32>,
+    items: BTreeMap<u32, ItemInfo>
+}
+
+impl Snapshot {
+    async fn load(connection: &Connection, shelf_id: Option<&String>) -> Result<Snapshot, CliError> {
+        let shelves = read_shelves(connection.rpc.clone(), shelf_id.cloned()).await?;
+        let items = list_items(connection, shelf_id.cloned()).await?;
+        Ok(Snapshot {
+            shelves: shelves.shelves.into_iter().map(|s| (s.shelf_id, s.shelf_count)).collect(),
+            items: items.into_iter().map(|i| (i.object_id, i)).collect()
+        })
+    }
+
+    fn is_low(item: &ItemInfo, low_stock: Option<u32>) -> bool {
+        low_stock.map(|threshold| item.stock < threshold).unwrap_or(false)
+    }
+
+    // the first poll has nothing to compare with, it only reports items that are already low so a one-off check can alert
+    fn changes(previous: Option<&Snapshot>, current: &Snapshot, low_stock: Option<u32>, time: &str) -> Vec<WatchEventRecord> {
+        let mut events = Vec::new();
+        let previous = match previous {
+            Some(previous) => previous,
+            None => {
+                events.extend(current.items.values()
+                    .filter(|i| Snapshot::is_low(i, low_stock))
+                    .map(|i| WatchEventRecord::item(time, WatchEvent::LowStock, i, None, Some(i.stock))));
+                return events;
+            }
+        };
+
+        for (shelf_id, slots) in &current.shelves {
+            if !previous.shelves.contains_key(shelf_id) {
+                events.push(WatchEventRecord::shelf(time, WatchEvent::ShelfAdded, shelf_id, *slots));
+            }
+        }
+
+        for (item_id, item) in &current.items {
+            let old = match previous.items.get(item_id) {
+                Some(old) => old,
+                None => {
+                    events.push(WatchEventRecord::item(time, WatchEvent::ItemAdded, item, None, Some(item.stock)));
+                    if Snapshot::is_low(item, low_stock) {
+                        events.push(WatchEventRecord::item(time, WatchEvent::LowStock, item, None, Some(item.stock)));
+                    }
+                    continue;
+                }
+            };
+            if old.stock != item.stock {
+                events.push(WatchEventRecord::item(time, WatchEvent::StockChanged, item, Some(old.stock), Some(item.stock)));
+                match (Snapshot::is_low(old, low_stock), Snapshot::is_low(item, low_stock)) {
+                    (false, true) => events.push(WatchEventRecord::item(time, WatchEvent::LowStock, item, Some(old.stock), Some(item.stock))),
+                    (true, false) => events.push(WatchEventRecord::item(time, WatchEvent::Restocked, item, Some(old.stock), Some(item.stock))),
+                    _ => {}
+                }
+            }
+            if old.price != item.price {
+                events.push(WatchEventRecord::item(time, WatchEvent::PriceChanged, item, Some(old.price), Some(item.price)));
+            }
+        }
+
+        for (item_id, item) in &previous.items {
+            if !current.items.contains_key(item_id) {
+                events.push(WatchEventRecord::item(time, WatchEvent::ItemRemoved, item, Some(item.stock), None));
+            }
+        }
+        events
+    }
+}
+
+// a list can't be closed while polling goes on, so every format is written a record at a time
+struct EventWriter {
+    format: OutputFormat,
+    csv: Option<csv::Writer<io::Stdout>>
+}
+
+impl EventWriter {
+    fn new(format: OutputFormat) -> EventWriter {
+        EventWriter { format, csv: None }
+    }
+
+    fn write(&mut self, events: &[WatchEventRecord], money_format: &MoneyFormat) -> Result<(), CliError> {
+        let stdout = io::stdout();
+        let written = match self.format {
+            OutputFormat::Table => {
+                let mut out = stdout.lock();
+                events.iter().try_for_each(|e| writeln!(out, "{}  {}", e.time, e.describe(money_format)))
+            }
+            // a JSON array would never be closed, one object per line is the only form a consumer can read as it arrives
+            OutputFormat::Json | OutputFormat::Ndjson => {
+                let mut out = stdout.lock();
+                events.iter().try_for_each(|e| {
+                    serde_json::to_writer(&mut out, e).map_err(io::Error::from)?;
+                    writeln!(out)
+                })
+            }
+            OutputFormat::Csv => {
+                let writer = self.csv.get_or_insert_with(|| csv::Writer::from_writer(io::stdout()));
+                events.iter()
+                    .try_for_each(|e| writer.serialize(e))
+                    .map_err(io::Error::from)
+                    .and_then(|_| writer.flush())
+            }
+            OutputFormat::Yaml => {
+                let mut out = stdout.lock();
+                events.iter().try_for_each(|e| {
+                    writeln!(out, "---")?;
+                    serde_yaml::to_writer(&mut out, e).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+                })
+            }
+        };
+        written
+            .and_then(|_| io::stdout().flush())
+            .map_err(|e| CliError::Output(e.to_string()))
+    }
+}
+
+pub(crate) struct WatchOptions<'a> {
+    pub(crate) shelf_id: Option<&'a String>,
+    pub(crate) interval: Duration,
+    pub(crate) low_stock: Option<u32>,
+    pub(crate) count: Option<u64>,
+    pub(crate) exit_on_alert: bool
+}
+
+// an unreachable server is reported and retried on the next poll, anything else ends the watch
+pub(crate) async fn run_watch(connection: &Connection, options: WatchOptions<'_>, output: OutputFormat, money_format: &MoneyFormat) -> Result<(), CliError> {
+    let mut writer = EventWriter::new(output);
+    let mut previous: Option<Snapshot> = None;
+    let mut polls = 0;
+
+    loop {
+        match Snapshot::load(connection, options.shelf_id).await {
+            Ok(current) => {
+                let time = humantime::format_rfc3339_seconds(SystemTime::now()).to_string();
+                let events = Snapshot::changes(previous.as_ref(), &current, options.low_stock, &time);
+                writer.write(&events, money_format)?;
+
+                let alerts = events.iter().filter(|e| e.is_alert()).collect::<Vec<&WatchEventRecord>>();
+                if options.exit_on_alert && !alerts.is_empty() {
+                    let items = alerts.iter().filter_map(|e| e.item_id).map(|id| id.to_string()).collect::<Vec<String>>();
+                    return Err(CliError::Alert(format!("low stock on item {}", items.join(", "))));
+                }
+                previous = Some(current);
+            }
+            Err(CliError::Connection(reason)) => eprintln!("could not reach the server, retrying: {}", reason),
+            Err(e) => return Err(e)
+        }
+
+        polls += 1;
+        if options.count.map(|count| polls >= count).unwrap_or(false) {
+            return Ok(());
+        }
+        tokio::time::sleep(options.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(object_id: u32, stock: u32, price: u32) -> ItemInfo {
+        ItemInfo { object_id, shelf_id: "A1".to_owned(), description: format!("item {}", object_id), stock, price }
+    }
+
+    fn snapshot(shelves: &[(&str, u32)], items: Vec<ItemInfo>) -> Snapshot {
+        Snapshot {
+            shelves: shelves.iter().map(|(id, slots)| (id.to_string(), *slots)).collect(),
+            items: items.into_iter().map(|i| (i.object_id, i)).collect()
+        }
+    }
+
+    fn events(records: &[WatchEventRecord]) -> Vec<(WatchEvent, Option<u32>)> {
+        records.iter().map(|e| (e.event, e.item_id)).collect()
+    }
+
+    #[test]
+    fn the_first_poll_only_reports_low_items() {
+        let current = snapshot(&[("A1", 4)], vec![item(1, 2, 100), item(2, 9, 100)]);
+
+        assert_eq!(events(&Snapshot::changes(None, &current, Some(5), "now")), vec![(WatchEvent::LowStock, Some(1))]);
+        assert!(Snapshot::changes(None, &current, None, "now").is_empty());
+    }
+
+    #[test]
+    fn reports_every_difference_between_polls() {
+        let previous = snapshot(&[("A1", 4)], vec![item(1, 10, 100), item(2, 2, 50), item(3, 5, 75)]);
+        let current = snapshot(&[("A1", 4), ("B2", 6)], vec![item(1, 3, 120), item(2, 8, 50), item(4, 1, 20)]);
+
+        let records = Snapshot::changes(Some(&previous), &current, Some(5), "now");
+        assert_eq!(events(&records), vec![
+            (WatchEvent::ShelfAdded, None),
+            (WatchEvent::StockChanged, Some(1)),
+            (WatchEvent::LowStock, Some(1)),
+            (WatchEvent::PriceChanged, Some(1)),
+            (WatchEvent::StockChanged, Some(2)),
+            (WatchEvent::Restocked, Some(2)),
+            (WatchEvent::ItemAdded, Some(4)),
+            (WatchEvent::LowStock, Some(4)),
+            (WatchEvent::ItemRemoved, Some(3))
+        ]);
+        assert_eq!((records[3].old, records[3].new), (Some(100), Some(120)));
+        assert_eq!((records[8].old, records[8].new), (Some(5), None));
+    }
+
+    #[test]
+    fn nothing_changed_reports_nothing() {
+        let previous = snapshot(&[("A1", 4)], vec![item(1, 2, 100)]);
+        let current = snapshot(&[("A1", 4)], vec![item(1, 2, 100)]);
+
+        assert!(Snapshot::changes(Some(&previous), &current, Some(5), "now").is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+// the client library against the in-process server:
+// cargo test --features mock-server
+use std::future::Future;
+use cs4471_sims_cli_client::{ClientError, Shelf, SimsClient, TlsOptions};
+use cs4471_sims_cli_client::mock::MockServer;
+
+fn run(test: impl Future<Output = ()>) {
+    tokio::runtime::Runtime::new().expect("could not start the async runtime").block_on(test)
+}
+
+async fn logged_in(server: &MockServer) -> SimsClient {
+    server.add_user("alice", "secret");
+    let mut client = SimsClient::connect(server.address(), TlsOptions::default()).await.unwrap();
+    client.login("alice", "secret").await.unwrap();
+    client
+}
+
+#[test]
+fn creates_a_shelf_and_reads_its_items() {
+    run(async {
+        let server = MockServer::start().await.unwrap();
+        let mut client = logged_in(&server).await;
+
+        client.create_shelf("A1", 4).await.unwrap();
+        assert_eq!(client.shelves().await.unwrap(), vec![Shelf { id: "A1".to_owned(), slots: 4 }]);
+
+        let item_id = server.add_item("A1", "Paper towels", 20, 499).unwrap();
+        let items = client.items(Some("A1")).await.unwrap();
+        assert_eq!(items, server.items());
+        let item = client.item(item_id).await.unwrap();
+        assert_eq!((item.name.as_str(), item.stock, item.price), ("Paper towels", 20, 499));
+    });
+}
+
+#[test]
+fn a_wrong_password_is_unauthenticated() {
+    run(async {
+        let server = MockServer::start().await.unwrap();
+        server.add_user("alice", "secret");
+        let mut client = SimsClient::connect(server.address(), TlsOptions::default()).await.unwrap();
+
+        assert!(matches!(client.login("alice", "wrong").await, Err(ClientError::Unauthenticated(_))));
+        assert!(matches!(client.shelves().await, Err(ClientError::NotLoggedIn)));
+    });
+}
+
+#[test]
+fn an_unknown_item_is_not_found() {
+    run(async {
+        let server = MockServer::start().await.unwrap();
+        let mut client = logged_in(&server).await;
+
+        assert!(matches!(client.item(42).await, Err(ClientError::NotFound(_))));
+    });
+}